@@ -0,0 +1,17 @@
+use crate::AsmArgs;
+use reader::fxr::{asm::assemble::assemble, fxr_parser_with_sections::parse_fxr};
+use std::{error::Error, fs, process::ExitCode};
+
+/// Assembles `fxr-asm` text into a binary file.
+///
+/// The output is written even if it doesn't parse as an FXR file, since a half-finished edit is
+/// still worth keeping, but the parse error is reported as a warning.
+pub fn run(args: &AsmArgs) -> Result<ExitCode, Box<dyn Error>> {
+    let text = fs::read_to_string(&args.path)?;
+    let writer = assemble(&text).map_err(|e| format!("{}:{}", args.path.display(), e))?;
+    if let Err(e) = parse_fxr(writer.as_bytes()) {
+        eprintln!("warning: {}: {}", args.output.display(), e);
+    }
+    fs::write(&args.output, writer.into_bytes())?;
+    Ok(ExitCode::SUCCESS)
+}
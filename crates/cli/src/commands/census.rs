@@ -0,0 +1,66 @@
+use super::for_each_fxr_unchecked;
+use crate::{CensusArgs, OutputFormat};
+use reader::fxr::census::{Census, CensusOptions, FieldReport};
+use std::{error::Error, process::ExitCode};
+
+/// Parses every input file and prints field statistics per struct type
+pub fn run(args: &CensusArgs) -> Result<ExitCode, Box<dyn Error>> {
+    let mut census = Census::new();
+    let code = for_each_fxr_unchecked(&args.paths, |_, fxr| {
+        census.add(fxr);
+        Ok(())
+    })?;
+
+    let options = CensusOptions {
+        top_values: args.top,
+        min_correlation: args.min_correlation,
+    };
+    let mut report = census.report(options);
+    if let Some(section) = &args.section {
+        report.sections.retain(|s| s.kind == section);
+    }
+
+    match args.format {
+        OutputFormat::Json => println!("{}", serde_json::to_string(&report)?),
+        OutputFormat::Text => {
+            println!("{} files", report.files);
+            for section in &report.sections {
+                println!();
+                println!("{} ({} records)", section.kind, section.records);
+                println!(
+                    "  {:<18} {:>8} {:>12} {:>12} {:>6}  top values (count) / correlations",
+                    "field", "distinct", "min", "max", "float"
+                );
+                for field in &section.fields {
+                    print_field(field);
+                }
+            }
+        }
+    }
+    Ok(code)
+}
+
+fn print_field(field: &FieldReport) {
+    let top: Vec<String> = field
+        .top_values
+        .iter()
+        .map(|v| format!("{} ({})", v.value, v.count))
+        .collect();
+    println!(
+        "  {:<18} {:>8} {:>12} {:>12} {:>5.0}%  {}",
+        field.name,
+        field.distinct,
+        field.min,
+        field.max,
+        field.float_like * 100.0,
+        top.join(" ")
+    );
+    if !field.correlations.is_empty() {
+        let correlations: Vec<String> = field
+            .correlations
+            .iter()
+            .map(|c| format!("{} ({:+.2})", c.field, c.coefficient))
+            .collect();
+        println!("  {:<61}  ~ {}", "", correlations.join(", "));
+    }
+}
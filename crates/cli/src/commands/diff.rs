@@ -0,0 +1,39 @@
+use crate::{DiffArgs, OutputFormat};
+use reader::fxr::{
+    diff::{DiffOptions, diff},
+    fxr_parser_with_sections::parse_fxr,
+};
+use std::{error::Error, fs, process::ExitCode};
+
+/// Prints the structural differences between two files.
+///
+/// # Returns
+/// `ExitCode::SUCCESS` if the files are equivalent and `ExitCode::FAILURE` if they differ,
+/// following `diff(1)`.
+pub fn run(args: &DiffArgs) -> Result<ExitCode, Box<dyn Error>> {
+    let old_data = fs::read(&args.old)?;
+    let new_data = fs::read(&args.new)?;
+    let old = parse_fxr(&old_data).map_err(|e| format!("{}: {}", args.old.display(), e))?;
+    let new = parse_fxr(&new_data).map_err(|e| format!("{}: {}", args.new.display(), e))?;
+
+    let options = DiffOptions {
+        ignore_offsets: args.ignore_offsets,
+    };
+    let changes = diff(&old, &new, options);
+    match args.format {
+        OutputFormat::Text => {
+            println!("--- {}", args.old.display());
+            println!("+++ {}", args.new.display());
+            for change in &changes {
+                println!("{}", change);
+            }
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string(&changes)?),
+    }
+
+    Ok(if changes.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
@@ -0,0 +1,64 @@
+use crate::DiffDriverArgs;
+use reader::fxr::{
+    diff::{DiffOptions, diff},
+    fxr_parser_with_sections::parse_fxr,
+    textconv::{TextconvOptions, canonical_text},
+};
+use std::{error::Error, fs, path::Path, process::ExitCode};
+
+/// Prints a structural diff in the shape git expects from `diff.<driver>.command`.
+///
+/// Added and deleted files arrive as `/dev/null`; their whole canonical text is printed with a
+/// `+` or `-` prefix instead. Files that can't be read or parsed are reported inline rather than
+/// as an error, because a non-zero exit makes git abort the whole diff.
+pub fn run(args: &DiffDriverArgs) -> Result<ExitCode, Box<dyn Error>> {
+    println!("diff --fxr a/{} b/{}", args.path, args.path);
+    println!("--- a/{}", args.path);
+    println!("+++ b/{}", args.path);
+
+    let (old_data, new_data) = match (read_side(&args.old_file), read_side(&args.new_file)) {
+        (Ok(old), Ok(new)) => (old, new),
+        (Err(e), _) | (_, Err(e)) => {
+            println!("! {}", e);
+            return Ok(ExitCode::SUCCESS);
+        }
+    };
+    let (old, new) = match (&old_data, &new_data) {
+        (None, Some(data)) => return print_whole_file(data, '+'),
+        (Some(data), None) => return print_whole_file(data, '-'),
+        (Some(old), Some(new)) => (old, new),
+        (None, None) => return Ok(ExitCode::SUCCESS),
+    };
+
+    match (parse_fxr(old), parse_fxr(new)) {
+        (Ok(old), Ok(new)) => {
+            for change in diff(&old, &new, DiffOptions::default()) {
+                println!("{}", change);
+            }
+        }
+        (Err(e), _) | (_, Err(e)) => println!("! unable to parse: {}", e),
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Reads one side of the diff, returning `None` for git's `/dev/null` placeholder
+fn read_side(path: &Path) -> Result<Option<Vec<u8>>, String> {
+    if path == Path::new("/dev/null") {
+        return Ok(None);
+    }
+    fs::read(path)
+        .map(Some)
+        .map_err(|e| format!("unable to read {}: {}", path.display(), e))
+}
+
+fn print_whole_file(data: &[u8], prefix: char) -> Result<ExitCode, Box<dyn Error>> {
+    match parse_fxr(data) {
+        Ok(fxr) => {
+            for line in canonical_text(&fxr, TextconvOptions::default()).lines() {
+                println!("{}{}", prefix, line);
+            }
+        }
+        Err(e) => println!("! unable to parse: {}", e),
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,14 @@
+use crate::DisasmArgs;
+use reader::fxr::asm::disassemble::disassemble;
+use std::{error::Error, fs, process::ExitCode};
+
+/// Prints a file as `fxr-asm` text, or writes it to `--output`
+pub fn run(args: &DisasmArgs) -> Result<ExitCode, Box<dyn Error>> {
+    let data = fs::read(&args.path)?;
+    let text = disassemble(&data).map_err(|e| format!("{}: {}", args.path.display(), e))?;
+    match &args.output {
+        Some(output) => fs::write(output, text)?,
+        None => print!("{}", text),
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,64 @@
+use super::for_each_fxr;
+use crate::{InputArgs, OutputFormat};
+use serde_json::{Value, json};
+use std::{error::Error, fmt::Write, process::ExitCode};
+
+/// Prints the full parsed tree of every input file
+pub fn run(args: &InputArgs) -> Result<ExitCode, Box<dyn Error>> {
+    for_each_fxr(&args.paths, |path, fxr| {
+        let tree = serde_json::to_value(fxr)?;
+        match args.format {
+            OutputFormat::Text => {
+                let mut out = format!("{}\n", path.display());
+                write_value(&mut out, &tree, 1)?;
+                print!("{}", out);
+            }
+            OutputFormat::Json => {
+                let dump = json!({ "path": path, "fxr": tree });
+                println!("{}", serde_json::to_string(&dump)?);
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Writes `value` as an indented outline, one scalar per line.
+/// Empty sections (`null`) are left out to keep the output readable.
+fn write_value(out: &mut String, value: &Value, depth: usize) -> std::fmt::Result {
+    let indent = "  ".repeat(depth);
+    match value {
+        Value::Object(fields) => {
+            for (name, field) in fields.iter().filter(|(_, field)| !field.is_null()) {
+                if is_scalar(field) {
+                    writeln!(out, "{}{}: {}", indent, name, scalar(field))?;
+                } else {
+                    writeln!(out, "{}{}:", indent, name)?;
+                    write_value(out, field, depth + 1)?;
+                }
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                if is_scalar(item) {
+                    writeln!(out, "{}[{}]: {}", indent, i, scalar(item))?;
+                } else {
+                    writeln!(out, "{}[{}]:", indent, i)?;
+                    write_value(out, item, depth + 1)?;
+                }
+            }
+        }
+        _ => writeln!(out, "{}{}", indent, scalar(value))?,
+    }
+    Ok(())
+}
+
+fn is_scalar(value: &Value) -> bool {
+    !matches!(value, Value::Object(_) | Value::Array(_))
+}
+
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,76 @@
+use super::for_each_fxr;
+use crate::{ExportArgs, ExportFormat};
+use reader::fxr::{
+    layout::struct_named,
+    records::records,
+    walk::{field_json, format_offset},
+};
+use serde_json::{Map, Value};
+use std::{
+    error::Error,
+    fs::File,
+    io::{self, BufWriter, Write},
+    process::ExitCode,
+};
+
+/// Writes every struct of the requested type in every input file as one row, prefixed with the
+/// file name, ffx_id, section path and absolute offset. Offsets are written as `0x1A0`, the same
+/// as in `fxr dump` and `fxr query`.
+pub fn run(args: &ExportArgs) -> Result<ExitCode, Box<dyn Error>> {
+    let layout =
+        struct_named(&args.kind).ok_or_else(|| format!("unknown struct `{}`", args.kind))?;
+    let out: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+
+    match args.format {
+        ExportFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(out);
+            let mut header = vec!["file", "ffx_id", "path", "offset"];
+            header.extend(layout.iter().map(|span| span.name));
+            writer.write_record(&header)?;
+            let code = for_each_fxr(&args.paths, |path, fxr| {
+                let file = path.display().to_string();
+                let ffx_id = fxr.header.ffx_id.to_string();
+                for record in records(fxr, &args.kind) {
+                    let mut row = vec![
+                        file.clone(),
+                        ffx_id.clone(),
+                        record.path.to_string(),
+                        format_offset(record.offset),
+                    ];
+                    row.extend(record.fields().map(|(name, value)| {
+                        match field_json(name, value) {
+                            Value::String(text) => text,
+                            number => number.to_string(),
+                        }
+                    }));
+                    writer.write_record(&row)?;
+                }
+                Ok(())
+            })?;
+            writer.flush()?;
+            Ok(code)
+        }
+        ExportFormat::Jsonl => {
+            let mut out = out;
+            let code = for_each_fxr(&args.paths, |path, fxr| {
+                for record in records(fxr, &args.kind) {
+                    let mut row = Map::new();
+                    row.insert("file".into(), path.display().to_string().into());
+                    row.insert("ffx_id".into(), fxr.header.ffx_id.into());
+                    row.insert("path".into(), record.path.to_string().into());
+                    row.insert("offset".into(), format_offset(record.offset).into());
+                    for (name, value) in record.fields() {
+                        row.insert(name.into(), field_json(name, value));
+                    }
+                    writeln!(out, "{}", Value::Object(row))?;
+                }
+                Ok(())
+            })?;
+            out.flush()?;
+            Ok(code)
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use super::for_each_fxr_unchecked;
+use crate::{InferRulesArgs, OutputFormat};
+use reader::fxr::{
+    census::Census,
+    rules::{InferOptions, RuleComparison, RuleStatus, compare_with_validators, infer_profile},
+};
+use std::{error::Error, fs, process::ExitCode};
+
+/// Infers a rule profile from every input file and compares it with the built-in validator.
+///
+/// # Returns
+/// `ExitCode::FAILURE` if a file couldn't be read or parsed or the corpus violates a built-in rule.
+pub fn run(args: &InferRulesArgs) -> Result<ExitCode, Box<dyn Error>> {
+    let mut census = Census::new();
+    let mut code = for_each_fxr_unchecked(&args.paths, |_, fxr| {
+        census.add(fxr);
+        Ok(())
+    })?;
+
+    let options = InferOptions {
+        max_enum_values: args.max_enum,
+    };
+    let profile = infer_profile(&census, &args.name, options);
+    if let Some(output) = &args.output {
+        fs::write(output, profile.to_json()?)?;
+    }
+
+    let mut comparisons = compare_with_validators(&census, &profile);
+    if comparisons.iter().any(|c| c.status == RuleStatus::Violated) {
+        code = ExitCode::FAILURE;
+    }
+    if args.mismatches {
+        comparisons.retain(|c| c.status != RuleStatus::Matches);
+    }
+
+    match args.format {
+        OutputFormat::Json => println!("{}", serde_json::to_string(&comparisons)?),
+        OutputFormat::Text => {
+            for comparison in &comparisons {
+                print_comparison(comparison);
+            }
+        }
+    }
+    Ok(code)
+}
+
+fn print_comparison(comparison: &RuleComparison) {
+    let status = match comparison.status {
+        RuleStatus::Matches => "ok",
+        RuleStatus::Violated => "VIOLATED",
+        RuleStatus::TooLoose => "too loose",
+        RuleStatus::Unchecked => "unchecked",
+    };
+    println!(
+        "{:<10} {}.{} {}",
+        status, comparison.kind, comparison.field, comparison.inferred
+    );
+    if !comparison.rejected.is_empty() {
+        let rejected: Vec<String> = comparison
+            .rejected
+            .iter()
+            .map(|v| format!("{} ({})", v.value, v.count))
+            .collect();
+        println!("           validator rejects {}", rejected.join(" "));
+    }
+    if comparison.status == RuleStatus::TooLoose {
+        let accepted: Vec<String> = comparison
+            .accepted_unobserved
+            .iter()
+            .map(u64::to_string)
+            .collect();
+        println!("           validator accepts unseen {}", accepted.join(" "));
+    }
+}
@@ -0,0 +1,70 @@
+use super::for_each_fxr;
+use crate::{InputArgs, OutputFormat};
+use reader::fxr::{Header, walk::format_offset};
+use serde_json::json;
+use std::{error::Error, process::ExitCode};
+use zerocopy::IntoBytes;
+
+/// Prints the header summary of every input file
+pub fn run(args: &InputArgs) -> Result<ExitCode, Box<dyn Error>> {
+    for_each_fxr(&args.paths, |path, fxr| {
+        let header: &Header = &fxr.header;
+        let sections = section_table(header);
+        match args.format {
+            OutputFormat::Text => {
+                println!("{}", path.display());
+                println!(
+                    "  magic: {}  version: {}  ffx_id: {}",
+                    String::from_utf8_lossy(header.magic.as_bytes()).trim_end_matches('\0'),
+                    header.version,
+                    header.ffx_id
+                );
+                for (name, offset, count) in sections {
+                    println!(
+                        "  {:<10} count: {:<6} offset: 0x{:08X}",
+                        name, count, offset
+                    );
+                }
+            }
+            OutputFormat::Json => {
+                let sections: serde_json::Map<String, serde_json::Value> = sections
+                    .iter()
+                    .map(|(name, offset, count)| {
+                        (
+                            name.to_string(),
+                            json!({ "offset": format_offset(offset), "count": count }),
+                        )
+                    })
+                    .collect();
+                let info = json!({
+                    "path": path,
+                    "version": header.version,
+                    "ffx_id": header.ffx_id,
+                    "sections": sections,
+                });
+                println!("{}", serde_json::to_string(&info)?);
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Returns `(name, offset, count)` for every section listed in the header
+fn section_table(header: &Header) -> [(&'static str, u32, u32); 14] {
+    [
+        ("section1", header.section1_offset, header.section1_count),
+        ("section2", header.section2_offset, header.section2_count),
+        ("section3", header.section3_offset, header.section3_count),
+        ("section4", header.section4_offset, header.section4_count),
+        ("section5", header.section5_offset, header.section5_count),
+        ("section6", header.section6_offset, header.section6_count),
+        ("section7", header.section7_offset, header.section7_count),
+        ("section8", header.section8_offset, header.section8_count),
+        ("section9", header.section9_offset, header.section9_count),
+        ("section10", header.section10_offset, header.section10_count),
+        ("section11", header.section11_offset, header.section11_count),
+        ("section12", header.section12_offset, header.section12_count),
+        ("section13", header.section13_offset, header.section13_count),
+        ("section14", header.section14_offset, header.section14_count),
+    ]
+}
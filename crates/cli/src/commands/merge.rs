@@ -0,0 +1,31 @@
+use crate::{MergeArgs, OutputFormat};
+use reader::fxr::merge::merge;
+use std::{error::Error, fs, process::ExitCode};
+
+/// Merges two edited versions of a file against their common ancestor.
+///
+/// The result is only written when the merge is clean.
+///
+/// # Returns
+/// `ExitCode::SUCCESS` on a clean merge and `ExitCode::FAILURE` if there were conflicts.
+pub fn run(args: &MergeArgs) -> Result<ExitCode, Box<dyn Error>> {
+    let base = fs::read(&args.base)?;
+    let ours = fs::read(&args.ours)?;
+    let theirs = fs::read(&args.theirs)?;
+    let result = merge(&base, &ours, &theirs)?;
+
+    match args.format {
+        OutputFormat::Text => {
+            for conflict in &result.conflicts {
+                println!("{}", conflict);
+            }
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string(&result.conflicts)?),
+    }
+
+    if !result.is_clean() {
+        return Ok(ExitCode::FAILURE);
+    }
+    fs::write(&args.output, &result.bytes)?;
+    Ok(ExitCode::SUCCESS)
+}
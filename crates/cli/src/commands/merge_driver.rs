@@ -0,0 +1,26 @@
+use crate::MergeDriverArgs;
+use reader::fxr::merge::merge;
+use std::{error::Error, fs, process::ExitCode};
+
+/// Merge driver for git (`merge.<driver>.driver`).
+///
+/// On a clean merge the result replaces our version, which is where git picks it up. On
+/// conflicts our version is left untouched, the conflicts are listed on stderr and the non-zero
+/// exit code makes git mark the file as conflicted.
+pub fn run(args: &MergeDriverArgs) -> Result<ExitCode, Box<dyn Error>> {
+    let name = args.path.as_deref().unwrap_or("fxr");
+    let base = fs::read(&args.base)?;
+    let ours = fs::read(&args.ours)?;
+    let theirs = fs::read(&args.theirs)?;
+    let result = merge(&base, &ours, &theirs).map_err(|e| format!("{}: {}", name, e))?;
+
+    if !result.is_clean() {
+        eprintln!("{}: {} conflict(s)", name, result.conflicts.len());
+        for conflict in &result.conflicts {
+            eprintln!("  {}", conflict);
+        }
+        return Ok(ExitCode::FAILURE);
+    }
+    fs::write(&args.ours, &result.bytes)?;
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,67 @@
+pub mod asm;
+pub mod census;
+pub mod diff;
+pub mod diff_driver;
+pub mod disasm;
+pub mod dump;
+pub mod export;
+pub mod infer_rules;
+pub mod info;
+pub mod merge;
+pub mod merge_driver;
+pub mod patch;
+pub mod query;
+pub mod sqlite;
+pub mod template;
+pub mod textconv;
+pub mod types;
+pub mod unpack;
+pub mod validate;
+
+use crate::inputs::expand_inputs;
+use reader::fxr::fxr_parser_with_sections::{ParsedFXR, parse_fxr, parse_fxr_unchecked};
+use std::{error::Error, fs, path::Path, process::ExitCode};
+
+/// Parses every input file and hands the result to `f`.
+///
+/// Files that fail to read or parse are reported on stderr and skipped, so one bad file in a
+/// directory doesn't hide the output for the rest.
+///
+/// # Returns
+/// `ExitCode::FAILURE` if any file was skipped, `ExitCode::SUCCESS` otherwise.
+pub fn for_each_fxr(
+    paths: &[String],
+    f: impl FnMut(&Path, &ParsedFXR) -> Result<(), Box<dyn Error>>,
+) -> Result<ExitCode, Box<dyn Error>> {
+    for_each_parsed(paths, parse_fxr, f)
+}
+
+/// Like [`for_each_fxr`], but without the built-in validation, so corpus statistics count files
+/// that break the built-in rules rather than dropping them.
+pub fn for_each_fxr_unchecked(
+    paths: &[String],
+    f: impl FnMut(&Path, &ParsedFXR) -> Result<(), Box<dyn Error>>,
+) -> Result<ExitCode, Box<dyn Error>> {
+    for_each_parsed(paths, parse_fxr_unchecked, f)
+}
+
+/// `parse_fxr` or `parse_fxr_unchecked`
+type Parser = fn(&[u8]) -> Result<ParsedFXR<'_>, Box<dyn Error>>;
+
+fn for_each_parsed(
+    paths: &[String],
+    parse: Parser,
+    mut f: impl FnMut(&Path, &ParsedFXR) -> Result<(), Box<dyn Error>>,
+) -> Result<ExitCode, Box<dyn Error>> {
+    let mut code = ExitCode::SUCCESS;
+    for path in expand_inputs(paths)? {
+        let result = fs::read(&path)
+            .map_err(Into::into)
+            .and_then(|data| parse(&data).and_then(|fxr| f(&path, &fxr)));
+        if let Err(e) = result {
+            eprintln!("{}: {}", path.display(), e);
+            code = ExitCode::FAILURE;
+        }
+    }
+    Ok(code)
+}
@@ -0,0 +1,27 @@
+use crate::PatchArgs;
+use reader::fxr::patch::Patch;
+use std::{error::Error, fs, process::ExitCode};
+
+/// Applies a patch exported from the TUI's edit journal.
+///
+/// Every change is checked against the bytes it expects to replace before anything is written,
+/// so a patch made for a different version of the file is refused as a whole.
+///
+/// # Returns
+/// `ExitCode::FAILURE` if a change doesn't match the file.
+pub fn run(args: &PatchArgs) -> Result<ExitCode, Box<dyn Error>> {
+    let patch = Patch::load(&args.patch)?;
+    let mut bytes = fs::read(&args.path)?;
+    if let Err(e) = patch.apply(&mut bytes) {
+        eprintln!("{}: {}", args.path.display(), e);
+        return Ok(ExitCode::FAILURE);
+    }
+    let output = args.output.as_ref().unwrap_or(&args.path);
+    fs::write(output, bytes)?;
+    println!(
+        "Applied {} change(s) to {}",
+        patch.changes.len(),
+        output.display()
+    );
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,36 @@
+use super::for_each_fxr;
+use crate::{OutputFormat, QueryArgs};
+use reader::fxr::{query::Query, walk::format_offset};
+use serde_json::json;
+use std::{error::Error, process::ExitCode};
+
+/// Prints every node matching the query in every input file, one match per line
+pub fn run(args: &QueryArgs) -> Result<ExitCode, Box<dyn Error>> {
+    let query: Query = args
+        .query
+        .parse()
+        .map_err(|e| format!("invalid query: {}", e))?;
+    for_each_fxr(&args.paths, |path, fxr| {
+        for node in query.select(fxr) {
+            match args.format {
+                OutputFormat::Text => println!(
+                    "{}: {} @0x{:X} ({})",
+                    path.display(),
+                    node.path,
+                    node.offset,
+                    node.section.kind()
+                ),
+                OutputFormat::Json => println!(
+                    "{}",
+                    json!({
+                        "file": path,
+                        "path": node.path,
+                        "offset": format_offset(node.offset),
+                        "kind": node.section.kind(),
+                    })
+                ),
+            }
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,186 @@
+use super::for_each_fxr;
+use crate::SqliteArgs;
+use reader::fxr::{
+    Section11Entry,
+    catalog::sections,
+    layout::{struct_for, structs},
+    walk::{SectionPath, walk},
+};
+use rusqlite::{Connection, params, params_from_iter, types::Value};
+use std::{collections::HashMap, error::Error, fs, process::ExitCode};
+
+/// Loads every input file into a new SQLite database.
+///
+/// Each struct type gets a table named after it (`Section6Entry` becomes `section6_entry`) with
+/// the file, the section path, the position within its array, the absolute offset and every
+/// field. Nested structs reference their parent row through a `<parent table>_id` column, and
+/// the `section11_value` view reads Section11 slots as typed values.
+pub fn run(args: &SqliteArgs) -> Result<ExitCode, Box<dyn Error>> {
+    if args.output.exists() {
+        if !args.force {
+            return Err(format!(
+                "{} already exists, pass --force to replace it",
+                args.output.display()
+            )
+            .into());
+        }
+        fs::remove_file(&args.output)?;
+    }
+
+    let mut connection = Connection::open(&args.output)?;
+    connection.execute_batch(&schema())?;
+    let mut transaction = connection.transaction()?;
+    let code = for_each_fxr(&args.paths, |path, fxr| {
+        // A file that fails halfway leaves nothing behind
+        let transaction = transaction.savepoint()?;
+        transaction.execute(
+            "INSERT INTO file (path, ffx_id) VALUES (?1, ?2)",
+            params![path.display().to_string(), fxr.header.ffx_id],
+        )?;
+        let file_id = transaction.last_insert_rowid();
+
+        let mut ids: HashMap<SectionPath, i64> = HashMap::new();
+        for node in walk(fxr) {
+            let kind = node.section.kind();
+            let mut columns = vec!["file_id".to_string()];
+            let mut values = vec![Value::Integer(file_id)];
+            if let Some(parent) = node.path.parent() {
+                let parent_kind = parent
+                    .segments()
+                    .last()
+                    .and_then(|segment| struct_for(*segment))
+                    .map(|(kind, _)| kind);
+                if let (Some(parent_kind), Some(id)) = (parent_kind, ids.get(&parent)) {
+                    columns.push(format!("{}_id", table_name(parent_kind)));
+                    values.push(Value::Integer(*id));
+                }
+            }
+
+            let position = node.path.segments().last().and_then(|s| s.index);
+            columns.extend(["path", "position", "offset"].map(String::from));
+            values.push(Value::Text(node.path.to_string()));
+            values.push(position.map_or(Value::Null, |i| Value::Integer(i as i64)));
+            values.push(Value::Integer(node.offset as i64));
+
+            for field in node.section.reflect() {
+                columns.push(field.name.to_string());
+                values.push(Value::Integer(field.value().as_u64() as i64));
+            }
+            if let Some(entry) = node.section.get::<Section11Entry>() {
+                columns.push("data_f32".to_string());
+                values.push(Value::Real(f32::from_bits(entry.data) as f64));
+            }
+
+            let quoted: Vec<String> = columns.iter().map(|c| quote(c)).collect();
+            let placeholders = vec!["?"; columns.len()].join(", ");
+            let sql = format!(
+                "INSERT INTO {} ({}) VALUES ({})",
+                table_name(kind),
+                quoted.join(", "),
+                placeholders
+            );
+            transaction
+                .prepare_cached(&sql)?
+                .execute(params_from_iter(values))?;
+            ids.insert(node.path, transaction.last_insert_rowid());
+        }
+        transaction.commit()?;
+        Ok(())
+    })?;
+    transaction.commit()?;
+    Ok(code)
+}
+
+/// Struct types a struct can be nested under: those with an offset field pointing at it. The
+/// header is left out, since `walk` puts the sections it points at beside it rather than under it.
+fn parent_kinds(kind: &str) -> Vec<&'static str> {
+    sections()
+        .into_iter()
+        .filter(|info| info.section.is_some_and(|section| section > 0))
+        .filter(|info| info.pointers.iter().any(|pointer| pointer.target == kind))
+        .map(|info| info.name)
+        .collect()
+}
+
+/// `Section11Entry` -> `section11_entry`
+fn table_name(kind: &str) -> String {
+    let mut name = String::new();
+    for (i, c) in kind.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 {
+                name.push('_');
+            }
+            name.push(c.to_ascii_lowercase());
+        } else {
+            name.push(c);
+        }
+    }
+    name
+}
+
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier)
+}
+
+fn schema() -> String {
+    let mut sql = String::from(
+        "PRAGMA foreign_keys = ON;\n\
+         CREATE TABLE file (id INTEGER PRIMARY KEY, path TEXT NOT NULL UNIQUE, ffx_id INTEGER NOT NULL);\n",
+    );
+    for (kind, layout) in structs() {
+        let table = table_name(kind);
+        let mut columns = vec![
+            "id INTEGER PRIMARY KEY".to_string(),
+            "file_id INTEGER NOT NULL REFERENCES file(id)".to_string(),
+        ];
+        let parents: Vec<String> = parent_kinds(kind).iter().map(|p| table_name(p)).collect();
+        for parent in &parents {
+            columns.push(format!("{}_id INTEGER REFERENCES {}(id)", parent, parent));
+        }
+        columns.push("path TEXT NOT NULL".to_string());
+        columns.push("position INTEGER".to_string());
+        columns.push("\"offset\" INTEGER NOT NULL".to_string());
+        for span in layout {
+            columns.push(format!("{} INTEGER NOT NULL", quote(span.name)));
+        }
+        if kind == "Section11Entry" {
+            // The slot's bits read as an f32, whether or not that's what the slot holds. NaN is
+            // stored as NULL.
+            columns.push("data_f32 REAL".to_string());
+        }
+        sql.push_str(&format!(
+            "CREATE TABLE {} (\n    {}\n);\n",
+            table,
+            columns.join(",\n    ")
+        ));
+        sql.push_str(&format!(
+            "CREATE INDEX {}_file ON {} (file_id);\n",
+            table, table
+        ));
+        for parent in &parents {
+            sql.push_str(&format!(
+                "CREATE INDEX {}_{} ON {} ({}_id);\n",
+                table, parent, table, parent
+            ));
+        }
+    }
+
+    // Same rule as Section11Entry::value: a normal f32 of plausible magnitude is a float,
+    // anything else a signed integer
+    let is_float = format!(
+        "((data >> 23) & 255) BETWEEN 1 AND 254 AND abs(data_f32) BETWEEN {:?} AND {:?}",
+        1e-6f32 as f64, 1e9f32 as f64
+    );
+    let parents: Vec<String> = parent_kinds("Section11Entry")
+        .iter()
+        .map(|p| format!("{}_id", table_name(p)))
+        .collect();
+    sql.push_str(&format!(
+        "CREATE VIEW section11_value AS SELECT id, file_id, {}, path, position, \"offset\", data,\n\
+         CASE WHEN {is_float} THEN 'float' ELSE 'int' END AS value_type,\n\
+         CASE WHEN {is_float} THEN data_f32 ELSE data - ((data >> 31) << 32) END AS value\n\
+         FROM section11_entry;\n",
+        parents.join(", "),
+    ));
+    sql
+}
@@ -0,0 +1,17 @@
+use crate::{TemplateArgs, TemplateFormat};
+use reader::fxr::templates::{binary_template, imhex_pattern, kaitai_spec};
+use std::{error::Error, fs, process::ExitCode};
+
+/// Prints the generated template, or writes it to `--output`
+pub fn run(args: &TemplateArgs) -> Result<ExitCode, Box<dyn Error>> {
+    let text = match args.format {
+        TemplateFormat::Imhex => imhex_pattern(),
+        TemplateFormat::Bt => binary_template(),
+        TemplateFormat::Kaitai => serde_yaml_ng::to_string(&kaitai_spec())?,
+    };
+    match &args.output {
+        Some(output) => fs::write(output, text)?,
+        None => print!("{}", text),
+    }
+    Ok(ExitCode::SUCCESS)
+}
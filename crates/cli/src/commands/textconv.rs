@@ -0,0 +1,18 @@
+use crate::TextconvArgs;
+use reader::fxr::{
+    fxr_parser_with_sections::parse_fxr,
+    textconv::{TextconvOptions, canonical_text},
+};
+use std::{error::Error, fs, process::ExitCode};
+
+/// Prints the canonical text form of one file. git invokes this with the path of a temporary
+/// copy of the blob when `diff.<driver>.textconv` is set.
+pub fn run(args: &TextconvArgs) -> Result<ExitCode, Box<dyn Error>> {
+    let data = fs::read(&args.path)?;
+    let fxr = parse_fxr(&data).map_err(|e| format!("{}: {}", args.path.display(), e))?;
+    let options = TextconvOptions {
+        ignore_offsets: args.ignore_offsets,
+    };
+    print!("{}", canonical_text(&fxr, options));
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,20 @@
+use crate::{TypesArgs, TypesFormat};
+use reader::fxr::formats::{csharp_classes, python_classes, registry};
+use std::{error::Error, fs, process::ExitCode};
+
+/// Traces the section structs and prints their formats in the requested language, or writes them
+/// to `--output`
+pub fn run(args: &TypesArgs) -> Result<ExitCode, Box<dyn Error>> {
+    let registry = registry()?;
+    let text = match args.format {
+        TypesFormat::Yaml => serde_yaml_ng::to_string(&registry)?,
+        TypesFormat::Json => serde_json::to_string_pretty(&registry)? + "\n",
+        TypesFormat::Csharp => csharp_classes(&registry, &args.namespace),
+        TypesFormat::Python => python_classes(&registry),
+    };
+    match &args.output {
+        Some(output) => fs::write(output, text)?,
+        None => print!("{}", text),
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,59 @@
+use crate::UnpackArgs;
+use reader::fxr::archive::unpack;
+use std::{collections::HashSet, error::Error, fs, process::ExitCode};
+
+/// Lists or extracts the files of a BND4 archive, decompressing DFLT compressed DCX first.
+///
+/// Files are written under their last path component, so the Windows directories stored in the
+/// archive are dropped, and an archive holding two files with the same name is refused. A compressed lone file is written under the archive's name without
+/// `.dcx`.
+///
+/// # Returns
+/// `ExitCode::SUCCESS` once every file is listed or written.
+pub fn run(args: &UnpackArgs) -> Result<ExitCode, Box<dyn Error>> {
+    let files = unpack(&fs::read(&args.archive)?)?;
+    let archive_name = args
+        .archive
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned();
+    if args.list {
+        for file in &files {
+            let id = file.id.map(|id| id.to_string()).unwrap_or_default();
+            println!("{:>10}  {:>10}  {}", id, file.data.len(), file.name);
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let output = match &args.output {
+        Some(output) => output.clone(),
+        None => args.archive.with_file_name(archive_name.replace('.', "-")),
+    };
+    let names: Vec<&str> = files
+        .iter()
+        .map(|file| match file.file_name() {
+            "" => archive_name.trim_end_matches(".dcx"),
+            name => name,
+        })
+        .collect();
+    // Entries from different directories of the archive can share a name; refuse rather than let
+    // one overwrite the other
+    let mut seen = HashSet::new();
+    if let Some(duplicate) = names.iter().find(|name| !seen.insert(**name)) {
+        return Err(format!(
+            "{} holds more than one file named {}, see --list for their paths",
+            args.archive.display(),
+            duplicate
+        )
+        .into());
+    }
+
+    fs::create_dir_all(&output)?;
+    for (file, name) in files.iter().zip(names) {
+        let path = output.join(name);
+        fs::write(&path, &file.data)?;
+        println!("{}", path.display());
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,74 @@
+use crate::{OutputFormat, ValidateArgs, inputs::expand_inputs};
+use reader::fxr::{
+    fxr_parser_with_sections::{parse_fxr, parse_fxr_unchecked},
+    rules::Profile,
+};
+use serde_json::json;
+use std::{error::Error, fs, path::Path, process::ExitCode};
+use validator::Validate;
+
+/// Validates every input file and prints one diagnostic line per file.
+///
+/// # Returns
+/// `ExitCode::FAILURE` if any file failed to parse or validate.
+pub fn run(args: &ValidateArgs) -> Result<ExitCode, Box<dyn Error>> {
+    let profile = args.profile.as_ref().map(Profile::load).transpose()?;
+    let mut code = ExitCode::SUCCESS;
+    for path in expand_inputs(&args.input.paths)? {
+        let diagnostics = validate_file(&path, profile.as_ref());
+        if !diagnostics.is_empty() {
+            code = ExitCode::FAILURE;
+        }
+        match args.input.format {
+            OutputFormat::Text if diagnostics.is_empty() => println!("OK   {}", path.display()),
+            OutputFormat::Text => {
+                println!("FAIL {}", path.display());
+                for diagnostic in &diagnostics {
+                    println!("  {}", diagnostic);
+                }
+            }
+            OutputFormat::Json => {
+                let result = json!({
+                    "path": path,
+                    "valid": diagnostics.is_empty(),
+                    "errors": diagnostics,
+                });
+                println!("{}", serde_json::to_string(&result)?);
+            }
+        }
+    }
+    Ok(code)
+}
+
+/// Returns every problem found in the file at `path`, or an empty list if it is valid. With a
+/// profile, the file is checked against the profile's rules instead of the built-in ones, so a
+/// profile can relax them as well as add to them.
+fn validate_file(path: &Path, profile: Option<&Profile>) -> Vec<String> {
+    let data = match fs::read(path) {
+        Ok(data) => data,
+        Err(e) => return vec![format!("read error: {}", e)],
+    };
+    let parsed = match profile {
+        Some(_) => parse_fxr_unchecked(&data),
+        None => parse_fxr(&data),
+    };
+    let fxr = match parsed {
+        Ok(fxr) => fxr,
+        Err(e) => return vec![format!("parse error: {}", e)],
+    };
+    if let Some(profile) = profile {
+        return profile
+            .check(&fxr)
+            .iter()
+            .map(|violation| format!("{} rule: {}", profile.name, violation))
+            .collect();
+    }
+    match fxr.validate() {
+        Ok(()) => Vec::new(),
+        Err(errors) => errors
+            .to_string()
+            .lines()
+            .map(|line| format!("validation error: {}", line))
+            .collect(),
+    }
+}
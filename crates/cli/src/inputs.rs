@@ -0,0 +1,54 @@
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Expands command-line inputs into the `.fxr` files they refer to.
+///
+/// Files are passed through as-is, directories are walked recursively for `.fxr` files, and
+/// arguments containing glob metacharacters are expanded here because not every shell does it
+/// for us (notably `cmd.exe`). An existing path is always taken literally, so a file named
+/// `a[1].fxr` isn't read as a pattern.
+///
+/// # Errors
+/// Returns an error if a glob pattern is malformed, a path does not exist, or a directory can't
+/// be read.
+pub fn expand_inputs(inputs: &[String]) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut files = Vec::new();
+    for input in inputs {
+        let path = PathBuf::from(input);
+        if path.exists() {
+            push_path(&path, &mut files)?;
+        } else if input.contains(['*', '?', '[']) {
+            for entry in glob::glob(input)? {
+                push_path(&entry?, &mut files)?;
+            }
+        } else {
+            return Err(format!("{}: no such file or directory", input).into());
+        }
+    }
+    Ok(files)
+}
+
+fn push_path(path: &Path, files: &mut Vec<PathBuf>) -> Result<(), Box<dyn Error>> {
+    if path.is_dir() {
+        let mut entries = fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        entries.sort();
+        for entry in entries {
+            if entry.is_dir() || is_fxr(&entry) {
+                push_path(&entry, files)?;
+            }
+        }
+    } else {
+        files.push(path.to_path_buf());
+    }
+    Ok(())
+}
+
+fn is_fxr(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("fxr"))
+}
@@ -0,0 +1,341 @@
+#![deny(clippy::unwrap_used)]
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::{path::PathBuf, process::ExitCode};
+mod commands;
+mod inputs;
+
+/// Scriptable tools for inspecting `.fxr` files
+#[derive(Parser)]
+#[command(name = "fxr", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print a header summary: ffx_id, version and section counts
+    Info(InputArgs),
+    /// Print the full parsed tree
+    Dump(InputArgs),
+    /// Validate files, exiting with a non-zero code if any of them fail
+    Validate(ValidateArgs),
+    /// Compare two files structurally, exiting with 1 if they differ
+    Diff(DiffArgs),
+    /// Print the canonical line-oriented text form of a file, for `git diff` textconv
+    Textconv(TextconvArgs),
+    /// External diff driver for git (`diff.<driver>.command`), see the README for setup
+    DiffDriver(DiffDriverArgs),
+    /// Three-way merge of two edited versions, exiting with 1 if there are conflicts
+    Merge(MergeArgs),
+    /// Merge driver for git (`merge.<driver>.driver`), see the README for setup
+    MergeDriver(MergeDriverArgs),
+    /// Print the nodes matching a query, e.g. `Section4::Section6[*] where unk00 == 603`
+    Query(QueryArgs),
+    /// Report distinct values, ranges and correlations of every field across many files
+    Census(CensusArgs),
+    /// Export every struct of one type across many files as CSV or JSON lines
+    Export(ExportArgs),
+    /// Infer field rules from a corpus and compare them with the built-in validator
+    InferRules(InferRulesArgs),
+    /// Load files into a SQLite database with one table per struct type
+    Sqlite(SqliteArgs),
+    /// Print a file as editable fxr-asm text
+    Disasm(DisasmArgs),
+    /// Assemble fxr-asm text back into a binary file
+    Asm(AsmArgs),
+    /// Generate an ImHex pattern, 010 Editor template or Kaitai Struct spec from the section
+    /// structs
+    Template(TemplateArgs),
+    /// Describe the JSON form of every section struct as YAML/JSON, C# or Python
+    Types(TypesArgs),
+    /// Reapply a patch exported from the TUI's edit journal, checking the old bytes first
+    Patch(PatchArgs),
+    /// List or extract the files of a BND4 archive, decompressing DCX first
+    Unpack(UnpackArgs),
+}
+
+#[derive(Args)]
+pub struct InputArgs {
+    /// Files, directories or glob patterns to read
+    #[arg(required = true)]
+    pub paths: Vec<String>,
+    /// Output format. JSON is written as one object per line, one line per file
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+}
+
+#[derive(Args)]
+pub struct ValidateArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+    /// Check the rules of a JSON profile written by `fxr infer-rules` instead of the built-in
+    /// rules
+    #[arg(long)]
+    pub profile: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct DiffArgs {
+    pub old: PathBuf,
+    pub new: PathBuf,
+    /// Don't report `*_offset` fields, which shift whenever earlier data changes size
+    #[arg(long)]
+    pub ignore_offsets: bool,
+    /// Output format. JSON is written as a single array of changes
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+}
+
+#[derive(Args)]
+pub struct TextconvArgs {
+    pub path: PathBuf,
+    /// Leave out `*_offset` fields
+    #[arg(long)]
+    pub ignore_offsets: bool,
+}
+
+/// The seven arguments git passes to an external diff command
+#[derive(Args)]
+pub struct DiffDriverArgs {
+    pub path: String,
+    pub old_file: PathBuf,
+    pub old_hex: String,
+    pub old_mode: String,
+    pub new_file: PathBuf,
+    pub new_hex: String,
+    pub new_mode: String,
+}
+
+#[derive(Args)]
+pub struct MergeArgs {
+    /// The common ancestor
+    pub base: PathBuf,
+    pub ours: PathBuf,
+    pub theirs: PathBuf,
+    /// Where to write the merged file. Nothing is written if there are conflicts
+    #[arg(short, long)]
+    pub output: PathBuf,
+    /// Output format for the conflict list
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+}
+
+/// The arguments git passes to a merge driver configured as `%O %A %B %P`
+#[derive(Args)]
+pub struct MergeDriverArgs {
+    pub base: PathBuf,
+    /// Our version, overwritten with the result on a clean merge
+    pub ours: PathBuf,
+    pub theirs: PathBuf,
+    /// Path of the file in the repository, used in messages
+    pub path: Option<String>,
+}
+
+#[derive(Args)]
+pub struct QueryArgs {
+    /// Path pattern with an optional `where` condition
+    pub query: String,
+    /// Files, directories or glob patterns to search
+    #[arg(required = true)]
+    pub paths: Vec<String>,
+    /// Output format. JSON is written as one object per match
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+}
+
+#[derive(Args)]
+pub struct CensusArgs {
+    /// Files, directories or glob patterns to read
+    #[arg(required = true)]
+    pub paths: Vec<String>,
+    /// Only report this struct type, e.g. `Section6Entry`
+    #[arg(long)]
+    pub section: Option<String>,
+    /// Number of most frequent values to list per field
+    #[arg(long, default_value_t = 8)]
+    pub top: usize,
+    /// Smallest absolute correlation coefficient to report
+    #[arg(long, default_value_t = 0.9)]
+    pub min_correlation: f64,
+    /// Output format. JSON is written as a single document for the whole corpus
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+}
+
+#[derive(Args)]
+pub struct ExportArgs {
+    /// Struct type to export, e.g. `Section6Entry`
+    pub kind: String,
+    /// Files, directories or glob patterns to read
+    #[arg(required = true)]
+    pub paths: Vec<String>,
+    #[arg(long, value_enum, default_value_t = ExportFormat::Csv)]
+    pub format: ExportFormat,
+    /// Write to a file instead of stdout
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Csv,
+    /// One JSON object per struct
+    Jsonl,
+}
+
+#[derive(Args)]
+pub struct InferRulesArgs {
+    /// Files, directories or glob patterns to read
+    #[arg(required = true)]
+    pub paths: Vec<String>,
+    /// Name stored in the profile, usually the game the corpus comes from
+    #[arg(long, default_value = "corpus")]
+    pub name: String,
+    /// Fields with at most this many distinct values get a list of allowed values
+    #[arg(long, default_value_t = 8)]
+    pub max_enum: usize,
+    /// Write the inferred profile as JSON, for `fxr validate --profile`
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+    /// Only report rules that disagree with the built-in validator
+    #[arg(long)]
+    pub mismatches: bool,
+    /// Output format. JSON is written as a single array of comparisons
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+}
+
+#[derive(Args)]
+pub struct SqliteArgs {
+    /// Files, directories or glob patterns to read
+    #[arg(required = true)]
+    pub paths: Vec<String>,
+    /// Database file to create
+    #[arg(short, long)]
+    pub output: PathBuf,
+    /// Replace the database if it already exists
+    #[arg(long)]
+    pub force: bool,
+}
+
+#[derive(Args)]
+pub struct DisasmArgs {
+    pub path: PathBuf,
+    /// Write the text to a file instead of stdout
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct AsmArgs {
+    pub path: PathBuf,
+    /// Where to write the binary file
+    #[arg(short, long)]
+    pub output: PathBuf,
+}
+
+#[derive(Args)]
+pub struct TemplateArgs {
+    pub format: TemplateFormat,
+    /// Write to a file instead of stdout
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TemplateFormat {
+    /// ImHex pattern (`.hexpat`)
+    Imhex,
+    /// 010 Editor binary template (`.bt`)
+    #[value(name = "010")]
+    Bt,
+    /// Kaitai Struct specification (`.ksy`)
+    Kaitai,
+}
+
+#[derive(Args)]
+pub struct TypesArgs {
+    pub format: TypesFormat,
+    /// Namespace of the generated C# classes
+    #[arg(long, default_value = "Fxr")]
+    pub namespace: String,
+    /// Write to a file instead of stdout
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TypesFormat {
+    /// serde-reflection registry as YAML
+    Yaml,
+    /// serde-reflection registry as JSON
+    Json,
+    /// C# classes with System.Text.Json attributes
+    Csharp,
+    /// Python dataclasses
+    Python,
+}
+
+#[derive(Args)]
+pub struct PatchArgs {
+    /// File to patch
+    pub path: PathBuf,
+    /// Patch file written by the TUI
+    pub patch: PathBuf,
+    /// Write the patched file here instead of overwriting the input
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct UnpackArgs {
+    /// BND4 archive or DCX compressed file, e.g. `sfxbnd_c2010.ffxbnd.dcx`
+    pub archive: PathBuf,
+    /// Directory to extract into, by default the archive's name with dots replaced by dashes,
+    /// next to it
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+    /// Print each file's ID, size and stored path instead of extracting
+    #[arg(long)]
+    pub list: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match &cli.command {
+        Command::Info(args) => commands::info::run(args),
+        Command::Dump(args) => commands::dump::run(args),
+        Command::Validate(args) => commands::validate::run(args),
+        Command::Diff(args) => commands::diff::run(args),
+        Command::Textconv(args) => commands::textconv::run(args),
+        Command::DiffDriver(args) => commands::diff_driver::run(args),
+        Command::Merge(args) => commands::merge::run(args),
+        Command::MergeDriver(args) => commands::merge_driver::run(args),
+        Command::Query(args) => commands::query::run(args),
+        Command::Census(args) => commands::census::run(args),
+        Command::Export(args) => commands::export::run(args),
+        Command::InferRules(args) => commands::infer_rules::run(args),
+        Command::Sqlite(args) => commands::sqlite::run(args),
+        Command::Disasm(args) => commands::disasm::run(args),
+        Command::Asm(args) => commands::asm::run(args),
+        Command::Template(args) => commands::template::run(args),
+        Command::Types(args) => commands::types::run(args),
+        Command::Patch(args) => commands::patch::run(args),
+        Command::Unpack(args) => commands::unpack::run(args),
+    };
+
+    match result {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
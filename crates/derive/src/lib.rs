@@ -0,0 +1,336 @@
+#![deny(clippy::unwrap_used)]
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Fields, LitInt, LitStr, parse_macro_input};
+
+/// Derives the per-struct boilerplate of an FXR section struct from its declaration.
+///
+/// Generates `FieldLayout` (names, offsets and sizes), `Reflect`, `Display`, `ValidateSection`
+/// and an entry in the section catalog (`fxr::catalog`), which is where `walk::SectionRef`,
+/// `layout::struct_for` and the exporters learn about the struct. The struct must be `#[repr(C)]` and
+/// derive zerocopy's `FromBytes`, `IntoBytes`, `KnownLayout` and `Immutable`, and serde's
+/// `Serialize`.
+///
+/// Attributes:
+/// * `#[fxr(validate)]` on the struct - `ValidateSection` runs the `#[validate]` rules of
+///   `validator::Validate` instead of accepting everything
+/// * `#[fxr(section = 6, array)]` on the struct - where the struct sits in the parsed tree: the
+///   section number (`0` for the header) and whether it is read as an array. Structs without it
+///   aren't part of the tree.
+/// * `#[fxr(data)]` on a `u32` field - implement `U32Field` by returning it
+/// * `#[fxr(display)]` on a field - include it in `Display` as a decimal number
+/// * `#[fxr(display = "hex")]` on a field - include it in `Display` as `0x...`
+/// * `#[fxr(points_to = "Section8Entry", count = "section8_count")]` on an offset field - the
+///   parser reads `count` structs of that type at the offset. Add `single` when it reads one
+///   struct whenever the count is non-zero.
+///
+/// Offset fields (`*_offset`, and anything with `points_to`) must be serialized with
+/// `#[serde(with = "hex_formatted_bytes")]`, which the derive checks.
+///
+/// The contents of each field's `#[validate(...)]` attributes are recorded in the catalog as
+/// text, so tools can show the rule a field is checked against.
+#[proc_macro_derive(FxrSection, attributes(fxr))]
+pub fn derive_fxr_section(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+enum DisplayFormat {
+    Decimal,
+    Hex,
+}
+
+struct Pointer<'a> {
+    field: &'a syn::Ident,
+    target: Option<syn::Path>,
+    count: Option<LitStr>,
+    single: bool,
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let ty = &input.ident;
+    let name = ty.to_string();
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            ty,
+            "FxrSection only supports structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            ty,
+            "FxrSection needs a struct with named fields",
+        ));
+    };
+
+    let mut validate = false;
+    let mut section: Option<LitInt> = None;
+    let mut array = false;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("fxr"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("validate") {
+                validate = true;
+                Ok(())
+            } else if meta.path.is_ident("section") {
+                section = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("array") {
+                array = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `validate`, `section` or `array`"))
+            }
+        })?;
+    }
+    if array && section.is_none() {
+        return Err(syn::Error::new_spanned(
+            ty,
+            "`array` needs a `section` number",
+        ));
+    }
+
+    let mut idents = Vec::new();
+    let mut displayed = Vec::new();
+    let mut pointers = Vec::new();
+    let mut rules = Vec::new();
+    let mut data = None;
+    for field in &fields.named {
+        let Some(ident) = &field.ident else {
+            continue;
+        };
+        idents.push(ident);
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("validate"))
+        {
+            let list = attr.meta.require_list()?;
+            rules.push((ident.to_string(), rule_text(&list.tokens)));
+        }
+        let mut pointer = Pointer {
+            field: ident,
+            target: None,
+            count: None,
+            single: false,
+        };
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("fxr"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("points_to") {
+                    let target: LitStr = meta.value()?.parse()?;
+                    pointer.target = Some(target.parse()?);
+                    return Ok(());
+                }
+                if meta.path.is_ident("count") {
+                    pointer.count = Some(meta.value()?.parse()?);
+                    return Ok(());
+                }
+                if meta.path.is_ident("single") {
+                    pointer.single = true;
+                    return Ok(());
+                }
+                if meta.path.is_ident("data") {
+                    if data.replace(ident).is_some() {
+                        return Err(meta.error("only one field can be `data`"));
+                    }
+                    return Ok(());
+                }
+                if !meta.path.is_ident("display") {
+                    return Err(meta.error(
+                        "expected `display`, `display = \"hex\"`, `points_to`, `count`, `single` or `data`",
+                    ));
+                }
+                let format = match meta.value() {
+                    Ok(value) => {
+                        let format: LitStr = value.parse()?;
+                        match format.value().as_str() {
+                            "hex" => DisplayFormat::Hex,
+                            "decimal" => DisplayFormat::Decimal,
+                            _ => {
+                                return Err(syn::Error::new_spanned(
+                                    format,
+                                    "expected \"hex\" or \"decimal\"",
+                                ));
+                            }
+                        }
+                    }
+                    Err(_) => DisplayFormat::Decimal,
+                };
+                displayed.push((ident, format));
+                Ok(())
+            })?;
+        }
+        let is_offset = ident
+            .to_string()
+            .trim_end_matches(|c: char| c.is_ascii_digit())
+            .ends_with("_offset");
+        let is_hex = field.attrs.iter().any(|attr| {
+            attr.path().is_ident("serde")
+                && attr
+                    .meta
+                    .require_list()
+                    .is_ok_and(|list| list.tokens.to_string().contains("hex_formatted_bytes"))
+        });
+        if (is_offset || pointer.target.is_some()) && !is_hex {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "offset fields need `#[serde(with = \"hex_formatted_bytes\")]`, so JSON writes them \
+                 as hex like every other export",
+            ));
+        }
+        match (&pointer.target, &pointer.count) {
+            (Some(_), Some(count)) => {
+                let count_name = count.value();
+                if !fields
+                    .named
+                    .iter()
+                    .any(|field| field.ident.as_ref().is_some_and(|i| *i == count_name))
+                {
+                    return Err(syn::Error::new_spanned(
+                        count,
+                        format!("`{}` has no field `{}`", name, count_name),
+                    ));
+                }
+                pointers.push(pointer);
+            }
+            (None, None) if !pointer.single => {}
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    "pointer fields need both `points_to` and `count`",
+                ));
+            }
+        }
+    }
+
+    let names = idents.iter().map(|ident| ident.to_string());
+    let fxr = quote!(::fxr_binary_reader::fxr);
+
+    let mut pattern = format!("{} {{{{", name);
+    for (i, (ident, format)) in displayed.iter().enumerate() {
+        let separator = if i == 0 { " " } else { ", " };
+        let placeholder = match format {
+            DisplayFormat::Decimal => "{}",
+            DisplayFormat::Hex => "0x{:X}",
+        };
+        pattern.push_str(&format!("{}{}: {}", separator, ident, placeholder));
+    }
+    pattern.push_str(" }}");
+    let display_args = displayed.iter().map(|(ident, _)| quote!(self.#ident));
+
+    let validate_body = if validate {
+        quote!(#fxr::catalog::validator::Validate::validate(self))
+    } else {
+        quote!(::core::result::Result::Ok(()))
+    };
+    let validate_fn = format_ident!("__validate_{}", ty);
+
+    let pointers = pointers.iter().map(|pointer| {
+        let field = pointer.field.to_string();
+        let count = &pointer.count;
+        let target = &pointer.target;
+        let single = pointer.single;
+        quote! {
+            #fxr::catalog::Pointer {
+                field: #field,
+                count: #count,
+                target: <#target as #fxr::reflect::Reflect>::NAME,
+                single: #single,
+            }
+        }
+    });
+
+    let section = match &section {
+        Some(number) => quote!(::core::option::Option::Some(#number)),
+        None => quote!(::core::option::Option::None),
+    };
+
+    let u32_field = data.map(|field| {
+        quote! {
+            impl #fxr::U32Field for #ty {
+                fn data(&self) -> u32 {
+                    self.#field
+                }
+            }
+        }
+    });
+
+    let rules = rules.iter().map(|(field, rule)| {
+        quote! {
+            #fxr::catalog::FieldValidation {
+                field: #field,
+                rule: #rule,
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #fxr::layout::FieldLayout for #ty {
+            const FIELDS: &'static [#fxr::layout::FieldSpan] = &#fxr::layout::spans(
+                [#(#names),*],
+                [#(::core::mem::offset_of!(#ty, #idents)),*],
+                ::core::mem::size_of::<#ty>(),
+            );
+        }
+
+        impl #fxr::reflect::Reflect for #ty {
+            const NAME: &'static str = #name;
+        }
+
+        impl ::core::fmt::Display for #ty {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                ::core::write!(f, #pattern, #(#display_args),*)
+            }
+        }
+
+        impl #fxr::catalog::ValidateSection for #ty {
+            fn validate_section(&self) -> ::core::result::Result<(), #fxr::catalog::validator::ValidationErrors> {
+                #validate_body
+            }
+        }
+
+        #[allow(non_snake_case)]
+        fn #validate_fn(
+            bytes: &[u8],
+        ) -> ::core::result::Result<(), #fxr::catalog::validator::ValidationErrors> {
+            #fxr::catalog::validate_bytes::<#ty>(bytes)
+        }
+
+        #fxr::catalog::inventory::submit! {
+            #fxr::catalog::SectionInfo {
+                name: #name,
+                section: #section,
+                array: #array,
+                size: ::core::mem::size_of::<#ty>(),
+                fields: <#ty as #fxr::layout::FieldLayout>::FIELDS,
+                validate: #validate_fn,
+                trace: #fxr::catalog::trace_zeroed::<#ty>,
+                pointers: &[#(#pointers),*],
+                rules: &[#(#rules),*],
+            }
+        }
+
+        #u32_field
+    })
+}
+
+/// Renders attribute tokens the way they are usually written, e.g. `range(min = 0, max = 0)`
+/// rather than the `range (min = 0 , max = 0)` of `TokenStream::to_string`
+fn rule_text(tokens: &TokenStream2) -> String {
+    tokens
+        .to_string()
+        .replace(" (", "(")
+        .replace("( ", "(")
+        .replace(" )", ")")
+        .replace(" ,", ",")
+}
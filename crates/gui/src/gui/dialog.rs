@@ -0,0 +1,114 @@
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::{
+    Terminal,
+    layout::Rect,
+    prelude::Backend,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+use reader::fxr::util::ParseError;
+use std::{error::Error, io, path::Path, time::Duration};
+
+/// What to do with a file that couldn't be opened
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recovery {
+    /// Go back to the file browser
+    Back,
+    /// Open it again, leaving out the sections that fail to parse
+    Lenient,
+}
+
+/// Shows why a file couldn't be opened until the user picks what to do next
+/// # Arguments
+/// * `path` - The file that failed
+/// * `error` - Why it failed
+/// * `offer_lenient` - Whether opening the file leniently is offered
+pub fn error_dialog<B: Backend>(
+    terminal: &mut Terminal<B>,
+    path: &Path,
+    error: &(dyn Error + 'static),
+    offer_lenient: bool,
+) -> io::Result<Recovery> {
+    let key = |text: &'static str| Span::styled(text, Style::default().fg(Color::Cyan));
+    let mut lines = vec![
+        Line::from(format!("Couldn't open {}", path.display())),
+        Line::from(""),
+        Line::from(Span::styled(
+            error.to_string(),
+            Style::default().fg(Color::Red),
+        )),
+    ];
+    if let Some(offset) = error_offset(error) {
+        lines.push(Line::from(format!("Offset: 0x{:X}", offset)));
+    }
+    lines.push(Line::from(""));
+    if offer_lenient {
+        lines.push(Line::from(vec![
+            key("l"),
+            Span::raw(": open leniently, leaving out the sections that fail to parse"),
+        ]));
+    }
+    lines.push(Line::from(vec![
+        key("Enter/Esc"),
+        Span::raw(": back to the file browser"),
+    ]));
+
+    loop {
+        terminal.draw(|frame| {
+            let area = centered(frame.area(), 80, lines.len() as u16 + 4);
+            let dialog = Paragraph::new(lines.clone())
+                .wrap(Wrap { trim: false })
+                .block(
+                    Block::default()
+                        .title("Error")
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(Color::Red)),
+                );
+            frame.render_widget(Clear, area);
+            frame.render_widget(dialog, area);
+        })?;
+
+        if event::poll(Duration::from_millis(50))? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                match key.code {
+                    KeyCode::Char('l') if offer_lenient => return Ok(Recovery::Lenient),
+                    KeyCode::Enter | KeyCode::Esc | KeyCode::Char('q') => {
+                        return Ok(Recovery::Back);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+/// Offset named by the first `ParseError` in the error's chain
+fn error_offset(error: &(dyn Error + 'static)) -> Option<usize> {
+    let mut current = Some(error);
+    while let Some(error) = current {
+        if let Some(offset) = error
+            .downcast_ref::<ParseError>()
+            .and_then(ParseError::offset)
+        {
+            return Some(offset);
+        }
+        current = error.source();
+    }
+    None
+}
+
+/// A `width`% wide box of `height` rows in the middle of `area`
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = area.width * width / 100;
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
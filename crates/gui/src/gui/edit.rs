@@ -0,0 +1,343 @@
+use super::outline::{FieldRef, Node, NodeKind, Outline};
+use reader::fxr::{
+    Section11Entry, Section11Value,
+    catalog::{
+        section,
+        validator::{Validate, ValidationErrors},
+    },
+    fxr_parser_with_sections::parse_fxr,
+    patch::{Change, Journal, PatchError, describe_offset},
+    reflect::FieldType,
+};
+use std::{
+    error::Error,
+    fs, io,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+use zerocopy::FromBytes;
+
+/// Path given for a file read from stdin
+pub const STDIN: &str = "-";
+
+/// How a typed value is read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpretation {
+    /// `0x3F800000` or `3F800000`
+    Hex,
+    /// Signed or unsigned decimal, e.g. `-1` or `4294967295`
+    Int,
+    /// `f32`, only for 4-byte fields
+    Float,
+}
+
+impl Interpretation {
+    /// The interpretation a field is most likely edited in: float for Section11 slots that hold
+    /// one, decimal otherwise
+    pub fn guess(field: FieldRef, raw: &[u8]) -> Self {
+        match Section11Entry::read_from_bytes(raw) {
+            Ok(entry) if field.section == "Section11Entry" => match entry.value() {
+                Section11Value::Float(_) => Interpretation::Float,
+                Section11Value::Int(_) => Interpretation::Int,
+            },
+            _ => Interpretation::Int,
+        }
+    }
+
+    /// Hex -> Int -> Float -> Hex, skipping Float for fields narrower than an `f32`
+    pub fn next(self, ty: FieldType) -> Self {
+        match self {
+            Interpretation::Hex => Interpretation::Int,
+            Interpretation::Int if ty == FieldType::U32 => Interpretation::Float,
+            Interpretation::Int | Interpretation::Float => Interpretation::Hex,
+        }
+    }
+
+    /// The field's current bytes written the way this interpretation reads them
+    pub fn format(self, raw: &[u8], ty: FieldType) -> String {
+        let value = raw
+            .iter()
+            .rev()
+            .fold(0u64, |value, &byte| (value << 8) | u64::from(byte));
+        match self {
+            Interpretation::Hex => format!("0x{:0width$X}", value, width = ty.size() * 2),
+            Interpretation::Int => value.to_string(),
+            Interpretation::Float => format!("{:?}", f32::from_bits(value as u32)),
+        }
+    }
+
+    /// Reads typed text as the little-endian bytes of a field
+    /// # Arguments
+    /// * `text` - The typed value
+    /// * `ty` - Type of the field being edited
+    /// # Returns
+    /// `ty.size()` bytes, or a message saying why the text doesn't fit the field
+    pub fn encode(self, text: &str, ty: FieldType) -> Result<Vec<u8>, String> {
+        let text = text.trim();
+        let bits = ty.size() as u32 * 8;
+        let max = u64::MAX >> (64 - bits);
+        let value = match self {
+            Interpretation::Hex => {
+                let digits = text
+                    .strip_prefix("0x")
+                    .or_else(|| text.strip_prefix("0X"))
+                    .unwrap_or(text);
+                u64::from_str_radix(digits, 16)
+                    .map_err(|_| format!("`{}` is not a hex number", text))?
+            }
+            Interpretation::Int => {
+                let value: i64 = text
+                    .parse()
+                    .map_err(|_| format!("`{}` is not an integer", text))?;
+                // Negative values are stored as two's complement
+                let min = -(1i64 << (bits - 1));
+                if value < min || value > max as i64 {
+                    return Err(format!("{} doesn't fit in a {}", value, ty));
+                }
+                value as u64 & max
+            }
+            Interpretation::Float => {
+                if ty != FieldType::U32 {
+                    return Err(format!("a {} field can't hold an f32", ty));
+                }
+                let value: f32 = text
+                    .parse()
+                    .map_err(|_| format!("`{}` is not a float", text))?;
+                value.to_bits().into()
+            }
+        };
+        if value > max {
+            return Err(format!("{} doesn't fit in a {}", text, ty));
+        }
+        Ok(value.to_le_bytes()[..ty.size()].to_vec())
+    }
+}
+
+/// Text being typed in the status bar
+#[derive(Debug, Clone)]
+pub enum Prompt {
+    /// A new value for a field
+    Value {
+        field: FieldRef,
+        range: Range<usize>,
+        interpretation: Interpretation,
+        text: String,
+    },
+    /// Path to save the file to
+    SaveAs { text: String },
+    /// Path to export the edit journal to
+    ExportPatch { text: String },
+    /// Text to find in the tree's labels
+    Search { text: String },
+    /// Absolute offset to select the owner of
+    GoTo { text: String },
+}
+
+impl Prompt {
+    pub fn text_mut(&mut self) -> &mut String {
+        match self {
+            Prompt::Value { text, .. }
+            | Prompt::SaveAs { text }
+            | Prompt::ExportPatch { text }
+            | Prompt::Search { text }
+            | Prompt::GoTo { text } => text,
+        }
+    }
+}
+
+/// The bytes of the open file, with the edits made since it was last saved
+#[derive(Debug)]
+pub struct Editor {
+    pub path: PathBuf,
+    pub bytes: Vec<u8>,
+    /// Contents of `path` as of the last load or save
+    saved: Vec<u8>,
+    /// Whether this session has already backed up the file at `path`
+    backed_up: bool,
+    /// Bumped on every change to `bytes` and on every save, so views know when to rebuild
+    pub revision: usize,
+    /// Every edit of the session, for undo, redo and patch export
+    journal: Journal,
+}
+
+impl Editor {
+    /// Starts editing bytes read from `path`, or from stdin when `path` is [`STDIN`]
+    pub fn new(path: PathBuf, bytes: Vec<u8>) -> Self {
+        Self {
+            path,
+            saved: bytes.clone(),
+            bytes,
+            backed_up: false,
+            revision: 0,
+            journal: Journal::default(),
+        }
+    }
+
+    /// Whether the bytes came from stdin, so there is no file to save in place
+    pub fn is_stdin(&self) -> bool {
+        self.path == Path::new(STDIN)
+    }
+
+    /// The archive the bytes were extracted from, when `path` is `<archive>/<name>`, so there is
+    /// no file to save in place
+    pub fn archive(&self) -> Option<&Path> {
+        self.path.parent().filter(|parent| parent.is_file())
+    }
+
+    /// Where to offer saving the bytes: `path`, or `<name>` next to the archive it came from
+    pub fn save_path(&self) -> PathBuf {
+        match (self.archive(), self.path.file_name()) {
+            (Some(archive), Some(name)) => archive.with_file_name(name),
+            _ => self.path.clone(),
+        }
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.bytes != self.saved
+    }
+
+    /// Whether any byte in `range` differs from the saved file
+    pub fn is_changed(&self, range: &Range<usize>) -> bool {
+        self.bytes.get(range.clone()) != self.saved.get(range.clone())
+    }
+
+    /// Writes a new value for a field and records it in the journal
+    /// # Returns
+    /// An error if the value doesn't fit in the file, which is left untouched
+    pub fn write(&mut self, field: FieldRef, offset: usize, new: &[u8]) -> Result<(), PatchError> {
+        // Name the field by its place in the tree, unless earlier edits broke parsing
+        let path = parse_fxr(&self.bytes)
+            .ok()
+            .and_then(|fxr| describe_offset(&fxr, offset))
+            .unwrap_or_else(|| format!("{}.{}", field.section, field.name));
+        self.journal.write(&mut self.bytes, path, offset, new)?;
+        self.revision += 1;
+        Ok(())
+    }
+
+    /// Reverts the last edit
+    /// # Returns
+    /// The edit that was reverted, or `None` if there is nothing to undo
+    pub fn undo(&mut self) -> Result<Option<Change>, PatchError> {
+        let Some(change) = self.journal.undo(&mut self.bytes)?.cloned() else {
+            return Ok(None);
+        };
+        self.revision += 1;
+        Ok(Some(change))
+    }
+
+    /// Makes the last undone edit again
+    /// # Returns
+    /// The edit that was made, or `None` if there is nothing to redo
+    pub fn redo(&mut self) -> Result<Option<Change>, PatchError> {
+        let Some(change) = self.journal.redo(&mut self.bytes)?.cloned() else {
+            return Ok(None);
+        };
+        self.revision += 1;
+        Ok(Some(change))
+    }
+
+    /// Writes the edits in effect as a patch file, for `fxr patch`
+    /// # Returns
+    /// The number of changes written
+    pub fn export_patch(&self, path: &Path) -> Result<usize, Box<dyn Error>> {
+        let patch = self.journal.patch();
+        fs::write(path, patch.to_json()?)?;
+        Ok(patch.changes.len())
+    }
+
+    /// Overwrites the file, first copying it to `<name>.bak` on the first save of the session
+    /// # Returns
+    /// The backup path, when one was written
+    pub fn save(&mut self) -> io::Result<Option<PathBuf>> {
+        if self.is_stdin() {
+            return Err(io::Error::other(
+                "read from stdin, use F2 to save it to a file",
+            ));
+        }
+        if let Some(archive) = self.archive() {
+            return Err(io::Error::other(format!(
+                "extracted from {}, use F2 to save it to a file",
+                archive.display()
+            )));
+        }
+        let backup = if self.backed_up {
+            None
+        } else {
+            Some(backup(&self.path)?)
+        };
+        fs::write(&self.path, &self.bytes)?;
+        self.saved = self.bytes.clone();
+        self.backed_up = true;
+        self.revision += 1;
+        Ok(backup)
+    }
+
+    /// Writes the edited bytes to another file, backing that file up first if it exists, and
+    /// keeps editing the new file
+    /// # Returns
+    /// The backup path, when one was written
+    pub fn save_as(&mut self, path: PathBuf) -> io::Result<Option<PathBuf>> {
+        let backup = match path.exists() {
+            true => Some(backup(&path)?),
+            false => None,
+        };
+        fs::write(&path, &self.bytes)?;
+        self.path = path;
+        self.saved = self.bytes.clone();
+        self.backed_up = true;
+        self.revision += 1;
+        Ok(backup)
+    }
+
+    /// Whether the field would still pass its struct's `#[validate]` rules holding `new`
+    pub fn check_field(&self, field: FieldRef, range: &Range<usize>, new: &[u8]) -> bool {
+        let Some(info) = section(field.section) else {
+            return true;
+        };
+        let Some(original) = self
+            .bytes
+            .get(field.struct_offset..field.struct_offset + info.size)
+        else {
+            return true;
+        };
+        let mut bytes = original.to_vec();
+        let start = range.start - field.struct_offset;
+        bytes[start..start + new.len()].copy_from_slice(new);
+        !ValidationErrors::has_error(&(info.validate)(&bytes), field.name)
+    }
+
+    /// Parses the edited bytes and runs the file's `Validate` rules
+    /// # Returns
+    /// A parse error, or the names of the fields that fail validation
+    pub fn validation(&self) -> Result<(), String> {
+        let fxr = parse_fxr(&self.bytes).map_err(|err| format!("parse error: {}", err))?;
+        fxr.validate().map_err(|errors| {
+            let mut fields: Vec<&str> = errors.errors().keys().copied().collect();
+            fields.sort_unstable();
+            format!("invalid: {}", fields.join(", "))
+        })
+    }
+
+    /// Marks every field whose bytes changed since the last save with a trailing `*`
+    pub fn mark_changes(&self, outline: &mut Outline) {
+        fn mark(editor: &Editor, nodes: &mut [Node]) {
+            for node in nodes {
+                if node.kind == NodeKind::Field && editor.is_changed(&node.range) {
+                    node.label.push_str(" *");
+                }
+                mark(editor, &mut node.children);
+            }
+        }
+        mark(self, &mut outline.roots);
+    }
+}
+
+/// Copies `path` to `path` with `.bak` appended
+fn backup(path: &Path) -> io::Result<PathBuf> {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".bak");
+    let backup = PathBuf::from(name);
+    fs::copy(path, &backup)?;
+    Ok(backup)
+}
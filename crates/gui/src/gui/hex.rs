@@ -0,0 +1,171 @@
+use super::{
+    edit::Editor,
+    outline::{Node, NodeKind},
+    pane_block,
+};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+use std::ops::Range;
+
+const BYTES_PER_ROW: usize = 16;
+
+/// Colors cycled through for the fields of the selected struct, so adjacent fields stand apart
+const FIELD_COLORS: [Color; 2] = [Color::Blue, Color::Magenta];
+
+/// Scroll position and byte cursor of the hex dump pane
+#[derive(Debug, Default)]
+pub struct HexView {
+    /// Absolute offset of the byte under the cursor
+    pub cursor: usize,
+    /// First row shown
+    top_row: usize,
+    /// Number of rows that fit in the pane at the last draw
+    visible_rows: usize,
+}
+
+impl HexView {
+    /// Moves the cursor by `delta` bytes, clamped to the file
+    /// # Arguments
+    /// * `delta` - Bytes to move, negative to move backwards
+    /// * `len` - Length of the file
+    pub fn move_cursor(&mut self, delta: isize, len: usize) {
+        self.cursor = self
+            .cursor
+            .saturating_add_signed(delta)
+            .min(len.saturating_sub(1));
+        self.scroll_to_cursor();
+    }
+
+    /// Moves the cursor to the start of `range` and scrolls it into view
+    pub fn show(&mut self, range: &Range<usize>) {
+        self.cursor = range.start;
+        let first = range.start / BYTES_PER_ROW;
+        let last = range.end.saturating_sub(1) / BYTES_PER_ROW;
+        if first < self.top_row || last >= self.top_row + self.visible_rows.max(1) {
+            self.top_row = first;
+        }
+    }
+
+    pub fn page_rows(&self) -> usize {
+        self.visible_rows.max(1)
+    }
+
+    fn scroll_to_cursor(&mut self) {
+        let row = self.cursor / BYTES_PER_ROW;
+        if row < self.top_row {
+            self.top_row = row;
+        } else if row >= self.top_row + self.visible_rows.max(1) {
+            self.top_row = row + 1 - self.visible_rows.max(1);
+        }
+    }
+
+    /// Draws the dump with the selected node's bytes highlighted and unsaved edits in bold red.
+    ///
+    /// A selected struct has each field colored separately and labeled at the end of the row the
+    /// field starts on; a selected field is labeled with its own name.
+    pub fn render(
+        &mut self,
+        frame: &mut Frame<'_>,
+        area: Rect,
+        editor: &Editor,
+        selected: Option<&Node>,
+        focused: bool,
+    ) {
+        let block = pane_block(format!("Hex @ 0x{:X}", self.cursor), focused);
+        self.visible_rows = block.inner(area).height as usize;
+        self.scroll_to_cursor();
+
+        let fields: Vec<(&str, Range<usize>)> = match selected {
+            Some(node) if node.kind == NodeKind::Field => {
+                vec![(field_name(&node.label), node.range.clone())]
+            }
+            Some(node) => node
+                .children
+                .iter()
+                .filter(|child| child.kind == NodeKind::Field)
+                .map(|child| (field_name(&child.label), child.range.clone()))
+                .collect(),
+            None => Vec::new(),
+        };
+        let highlight = selected.map(|node| node.range.clone()).unwrap_or(0..0);
+        let bytes = editor.bytes.as_slice();
+
+        let rows = bytes.len().div_ceil(BYTES_PER_ROW);
+        let lines: Vec<Line> = (self.top_row..rows.min(self.top_row + self.visible_rows))
+            .map(|row| {
+                let start = row * BYTES_PER_ROW;
+                let end = (start + BYTES_PER_ROW).min(bytes.len());
+                let mut spans = vec![Span::styled(
+                    format!("{:08X}  ", start),
+                    Style::default().fg(Color::DarkGray),
+                )];
+                for offset in start..start + BYTES_PER_ROW {
+                    let text = match bytes.get(offset) {
+                        Some(byte) => format!("{:02X}", byte),
+                        None => "  ".to_string(),
+                    };
+                    let mut style = byte_style(
+                        offset,
+                        &highlight,
+                        &fields,
+                        focused && offset == self.cursor,
+                    );
+                    if editor.is_changed(&(offset..offset + 1)) {
+                        style = style.fg(Color::LightRed).add_modifier(Modifier::BOLD);
+                    }
+                    spans.push(Span::styled(text, style));
+                    spans.push(Span::raw(if offset % 8 == 7 { "  " } else { " " }));
+                }
+                let ascii: String = bytes[start..end]
+                    .iter()
+                    .map(|&b| if b.is_ascii_graphic() { b as char } else { '.' })
+                    .collect();
+                spans.push(Span::raw(format!("|{:<16}|", ascii)));
+                let labels: Vec<&str> = fields
+                    .iter()
+                    .filter(|(_, range)| (start..end).contains(&range.start))
+                    .map(|(name, _)| *name)
+                    .collect();
+                if !labels.is_empty() {
+                    spans.push(Span::styled(
+                        format!(" {}", labels.join(", ")),
+                        Style::default().fg(Color::Cyan),
+                    ));
+                }
+                Line::from(spans)
+            })
+            .collect();
+
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+}
+
+fn byte_style(
+    offset: usize,
+    highlight: &Range<usize>,
+    fields: &[(&str, Range<usize>)],
+    cursor: bool,
+) -> Style {
+    let mut style = Style::default();
+    if let Some(i) = fields.iter().position(|(_, range)| range.contains(&offset)) {
+        style = style
+            .bg(FIELD_COLORS[i % FIELD_COLORS.len()])
+            .fg(Color::White);
+    } else if highlight.contains(&offset) {
+        style = style.bg(Color::Blue).fg(Color::White);
+    }
+    if cursor {
+        style = style.add_modifier(Modifier::REVERSED);
+    }
+    style
+}
+
+/// `unk04: 12` -> `unk04`
+fn field_name(label: &str) -> &str {
+    label.split(':').next().unwrap_or(label)
+}
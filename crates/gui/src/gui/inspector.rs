@@ -0,0 +1,135 @@
+use super::{
+    outline::{FieldRef, Node, NodeKind},
+    pane_block,
+};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+use reader::fxr::{
+    catalog::{section, validator::ValidationErrors},
+    reflect::FieldType,
+};
+
+/// Rows the inspector needs, borders included
+pub const HEIGHT: u16 = 10;
+
+/// The field to inspect for a tree selection: a field itself, or the only field of a one-field
+/// struct such as a `Section11Entry` slot
+pub fn inspected(node: Option<&Node>) -> Option<(&Node, FieldRef)> {
+    let node = node?;
+    if let Some(field) = node.field {
+        return Some((node, field));
+    }
+    match node.children.as_slice() {
+        [child] if node.kind == NodeKind::Struct => Some((child, child.field?)),
+        _ => None,
+    }
+}
+
+/// Draws every reading of the field's bytes, its absolute offset and the `#[validate]` rule it is
+/// checked against
+pub fn render(frame: &mut Frame<'_>, area: Rect, bytes: &[u8], node: &Node, field: FieldRef) {
+    let raw = &bytes[node.range.clone()];
+    let value = raw
+        .iter()
+        .rev()
+        .fold(0u32, |value, &byte| (value << 8) | u32::from(byte));
+    let bits = field.ty.size() * 8;
+
+    let label = |text: &str| Span::styled(format!("{:<8}", text), Style::default().fg(Color::Cyan));
+    let mut lines = vec![
+        Line::from(vec![
+            label("Offset"),
+            Span::raw(format!(
+                "0x{:X} ({} + 0x{:X})",
+                node.range.start,
+                field.section,
+                node.range.start - field.struct_offset
+            )),
+        ]),
+        Line::from(vec![
+            label("Hex"),
+            Span::raw(format!("0x{:0width$X}", value, width = field.ty.size() * 2)),
+        ]),
+    ];
+    lines.extend(match field.ty {
+        FieldType::U8 => vec![
+            Line::from(vec![label("u8"), Span::raw(value.to_string())]),
+            Line::from(vec![
+                label("i8"),
+                Span::raw((value as u8 as i8).to_string()),
+            ]),
+        ],
+        FieldType::U16 => vec![
+            Line::from(vec![label("u16"), Span::raw(value.to_string())]),
+            Line::from(vec![
+                label("i16"),
+                Span::raw((value as u16 as i16).to_string()),
+            ]),
+        ],
+        FieldType::U32 => vec![
+            Line::from(vec![label("u32"), Span::raw(value.to_string())]),
+            Line::from(vec![label("i32"), Span::raw((value as i32).to_string())]),
+            Line::from(vec![
+                label("f32"),
+                Span::raw(format!("{:?}", f32::from_bits(value))),
+            ]),
+        ],
+    });
+
+    let hex: Vec<String> = raw.iter().map(|byte| format!("{:02X}", byte)).collect();
+    let ascii: String = raw
+        .iter()
+        .map(|&b| if b.is_ascii_graphic() { b as char } else { '.' })
+        .collect();
+    lines.push(Line::from(vec![
+        label("Bytes"),
+        Span::raw(format!("{} |{}|", hex.join(" "), ascii)),
+    ]));
+
+    let nibbles: Vec<String> = (0..bits / 4)
+        .rev()
+        .map(|nibble| format!("{:04b}", (value >> (nibble * 4)) & 0xF))
+        .collect();
+    let set: Vec<String> = (0..bits)
+        .filter(|bit| value & (1 << bit) != 0)
+        .map(|bit| bit.to_string())
+        .collect();
+    let set = if set.is_empty() {
+        "none set".to_string()
+    } else {
+        format!("set: {}", set.join(", "))
+    };
+    lines.push(Line::from(vec![
+        label("Bits"),
+        Span::raw(format!("{} ({})", nibbles.join(" "), set)),
+    ]));
+    lines.push(rule_line(bytes, field, label("Rule")));
+
+    let title = format!("{}.{} ({})", field.section, field.name, field.ty);
+    frame.render_widget(Paragraph::new(lines).block(pane_block(title, false)), area);
+}
+
+/// The field's `#[validate]` rule and whether the struct's current bytes pass it
+fn rule_line<'a>(bytes: &[u8], field: FieldRef, label: Span<'a>) -> Line<'a> {
+    let Some(info) = section(field.section) else {
+        return Line::from(vec![label, Span::raw("unknown struct")]);
+    };
+    let Some(rule) = info.rule(field.name) else {
+        return Line::from(vec![label, Span::raw("none")]);
+    };
+    let result = bytes
+        .get(field.struct_offset..field.struct_offset + info.size)
+        .map(info.validate)
+        .unwrap_or(Ok(()));
+    let status = if ValidationErrors::has_error(&result, field.name) {
+        Span::styled(" (fails)", Style::default().fg(Color::Red))
+    } else {
+        Span::styled(" (passes)", Style::default().fg(Color::Green))
+    };
+    Line::from(vec![label, Span::raw(rule), status])
+}
@@ -1,33 +1,48 @@
+pub mod dialog;
+mod edit;
+pub mod hex;
+mod inspector;
+pub mod outline;
+mod search;
 mod view;
 use crate::{AppState, file_entries};
 use crossterm::{
-    event::{self, Event, KeyCode},
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
     style::Stylize,
 };
-use memmap2::Mmap;
+use dialog::error_dialog;
+pub use edit::STDIN;
+use edit::{Editor, Interpretation, Prompt};
+use outline::Outline;
 use ratatui::{
     Terminal,
     prelude::{Backend, CrosstermBackend},
-    style::{Modifier, Style},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, ListState},
 };
-use ratatui_tree_widget::{Tree, TreeItem};
+use ratatui_tree_widget::Tree;
 use reader::fxr::{
-    Section4Container,
-    fxr_parser_with_sections::{ParsedFXR, parse_fxr},
+    archive::ArchiveFile,
+    fxr_parser_with_sections::{parse_fxr, parse_fxr_lenient},
+    patch::describe_offset,
 };
+use search::Search;
 use std::{
-    any::type_name,
     env,
     error::Error,
-    fs::File,
     io,
-    ops::Deref,
-    path::PathBuf,
+    path::{Path, PathBuf},
     time::{Duration, Instant},
 };
 use view::*;
-use zerocopy::IntoBytes;
+
+/// Pane that receives the arrow keys in the node browser
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pane {
+    Tree,
+    Hex,
+}
 
 const HIGHLIGHT_STYLE: Style = Style {
     fg: Some(ratatui::style::Color::Yellow),
@@ -42,90 +57,116 @@ pub fn file_selection_loop<B: Backend>(
     files: (Vec<PathBuf>, Vec<String>), // Use PathBuf instead of String
     mut selected: usize,                // Add selected index as a parameter
 ) -> Option<Result<PathBuf, Box<dyn Error>>> {
-    let current_dir = env::current_dir().unwrap();
-
     loop {
         let mut list_state = ListState::default();
         list_state.select(Some(selected));
-        terminal
-            .draw(|frame| {
-                render_files_list_state(&files, list_state, frame as &mut ratatui::Frame);
-            })
-            .unwrap();
-
-        if crossterm::event::poll(Duration::from_millis(50)).unwrap() {
-            if let Event::Key(key) = event::read().unwrap() {
-                if key.kind == crossterm::event::KeyEventKind::Press {
-                    match key.code {
-                        KeyCode::Up => {
-                            increment_selected(&files, &mut selected);
-                        }
-                        KeyCode::Down => {
-                            decrement_selected(&files, &mut selected);
-                        }
-                        KeyCode::Right | KeyCode::Enter => {
-                            return terminal_enter_file_or_dir(
-                                terminal,
-                                &files,
-                                selected,
-                                &current_dir,
-                            );
-                        }
-                        KeyCode::Left => {
-                            return parent_pathbuf(terminal, &files, selected, &current_dir);
-                        }
-                        KeyCode::Esc => {
-                            println!("{}", "Exiting file selection".green());
-                            return None;
-                        }
-                        _ => {}
-                    }
+        if let Err(err) = terminal.draw(|frame| {
+            render_files_list_state(&files, list_state, frame as &mut ratatui::Frame);
+        }) {
+            return Some(Err(err.into()));
+        }
+
+        let key = match crossterm::event::poll(Duration::from_millis(50)) {
+            Ok(false) => continue,
+            Ok(true) => match event::read() {
+                Ok(Event::Key(key)) => key,
+                Ok(_) => continue,
+                Err(err) => return Some(Err(err.into())),
+            },
+            Err(err) => return Some(Err(err.into())),
+        };
+        if key.kind == crossterm::event::KeyEventKind::Press {
+            match key.code {
+                KeyCode::Up => {
+                    increment_selected(&files, &mut selected);
                 }
+                KeyCode::Down => {
+                    decrement_selected(&files, &mut selected);
+                }
+                KeyCode::Right | KeyCode::Enter => {
+                    return terminal_enter_file_or_dir(terminal, &files, selected);
+                }
+                KeyCode::Left => {
+                    return parent_pathbuf(terminal, &files, selected);
+                }
+                KeyCode::Esc => {
+                    println!("{}", "Exiting file selection".green());
+                    return None;
+                }
+                _ => {}
             }
         }
     }
 }
 
+/// The selected entry, or the current directory when the listing is empty
+fn selected_route(files: &(Vec<PathBuf>, Vec<String>), selected: usize) -> io::Result<PathBuf> {
+    match files.0.get(selected) {
+        Some(route) => Ok(route.clone()),
+        None => env::current_dir(),
+    }
+}
+
+/// Shows why `path` couldn't be browsed, then goes back to the same listing
+fn browser_error<B: Backend>(
+    terminal: &mut Terminal<B>,
+    path: &Path,
+    err: &(dyn Error + 'static),
+    files: &(Vec<PathBuf>, Vec<String>),
+    selected: usize,
+) -> Option<Result<PathBuf, Box<dyn Error>>> {
+    tracing::error!("Couldn't browse {}: {}", path.display(), err);
+    if let Err(err) = error_dialog(terminal, path, err, false) {
+        return Some(Err(err.into()));
+    }
+    file_selection_loop(terminal, files.clone(), selected)
+}
+
 fn parent_pathbuf<B: Backend>(
     terminal: &mut Terminal<B>,
     files: &(Vec<PathBuf>, Vec<String>),
     selected: usize,
-    current_dir: &PathBuf,
 ) -> Option<Result<PathBuf, Box<dyn Error>>> {
-    if let Some(parent) = files
-        .0
-        .get(selected)
-        .unwrap_or(current_dir)
-        .parent()
-        .unwrap()
-        .parent()
-    {
-        let dir_entries = file_entries(&parent.to_path_buf()).unwrap();
-        let current_dir_name = files.0[selected].file_name();
-        let new_selected = dir_entries
-            .0
-            .iter()
-            .position(|entry| entry.file_name() == current_dir_name)
-            .unwrap_or(0);
-        return file_selection_loop(terminal, dir_entries, new_selected);
+    let route = match selected_route(files, selected) {
+        Ok(route) => route,
+        Err(err) => return browser_error(terminal, Path::new("."), &err, files, selected),
+    };
+    // Already at the top, so stay in the listing
+    let Some(parent) = route.parent().and_then(Path::parent) else {
+        return file_selection_loop(terminal, files.clone(), selected);
+    };
+    match file_entries(&parent.to_path_buf()) {
+        Ok(dir_entries) => {
+            let current_dir_name = route.parent().and_then(Path::file_name);
+            let new_selected = dir_entries
+                .0
+                .iter()
+                .position(|entry| entry.file_name() == current_dir_name)
+                .unwrap_or(0);
+            file_selection_loop(terminal, dir_entries, new_selected)
+        }
+        Err(err) => browser_error(terminal, parent, err.as_ref(), files, selected),
     }
-    Some(Ok(current_dir.to_path_buf()))
 }
 
 fn terminal_enter_file_or_dir<B: Backend>(
     terminal: &mut Terminal<B>,
     files: &(Vec<PathBuf>, Vec<String>),
     selected: usize,
-    current_dir: &PathBuf,
 ) -> Option<Result<PathBuf, Box<dyn Error>>> {
-    let selected_route = files.0.get(selected).unwrap_or(current_dir);
+    let selected_route = match selected_route(files, selected) {
+        Ok(route) => route,
+        Err(err) => return browser_error(terminal, Path::new("."), &err, files, selected),
+    };
     if selected_route.is_dir() {
-        let dir_entries = file_entries(selected_route).unwrap();
-        file_selection_loop(terminal, dir_entries, 0)
+        match file_entries(&selected_route) {
+            Ok(dir_entries) => file_selection_loop(terminal, dir_entries, 0),
+            Err(err) => browser_error(terminal, &selected_route, err.as_ref(), files, selected),
+        }
     } else if selected_route.is_file() {
-        return Some(Ok(selected_route.clone()));
+        Some(Ok(selected_route))
     } else {
-        return None;
+        None
     }
 }
 
@@ -167,208 +208,679 @@ fn render_files_list_state(
     f.render_stateful_widget(list, size, &mut list_state);
 }
 
-fn get_type_name<T>(_: &T) -> &'static str {
-    type_name::<T>()
-}
+/// Lists the files of an archive until the user picks one or goes back
+/// # Arguments
+/// * `archive` - Path of the archive, for the title
+/// * `files` - The files to list
+/// # Returns
+/// The index of the picked file in `files`, or `None` when the user goes back to the browser
+pub fn archive_selection_loop<B: Backend>(
+    terminal: &mut Terminal<B>,
+    archive: &Path,
+    files: &[ArchiveFile],
+) -> io::Result<Option<usize>> {
+    let lines: Vec<String> = files
+        .iter()
+        .map(|file| {
+            let id = file.id.map(|id| id.to_string()).unwrap_or_default();
+            format!(
+                "{:<30} | ID: {:<10} | Size: {:<10} | {}",
+                file.file_name(),
+                id,
+                file.data.len(),
+                file.name
+            )
+        })
+        .collect();
+    let title = format!(
+        "{} | Enter: open  Esc: back to the file browser",
+        archive.display()
+    );
+    let mut selected = 0;
 
-fn get_class_name<'a, T>(instance: &T) -> &'a str {
-    let full_type_name = get_type_name(instance);
-    full_type_name.split("::").last().unwrap_or(full_type_name)
+    loop {
+        let mut list_state = ListState::default();
+        list_state.select(Some(selected));
+        terminal.draw(|frame| {
+            let items: Vec<ListItem> = lines
+                .iter()
+                .map(|line| ListItem::new(line.clone()))
+                .collect();
+            let list = List::new(items)
+                .block(Block::default().title(title.clone()).borders(Borders::ALL))
+                .highlight_style(HIGHLIGHT_STYLE)
+                .highlight_symbol(">> ");
+            frame.render_stateful_widget(list, frame.area(), &mut list_state);
+        })?;
+
+        if !crossterm::event::poll(Duration::from_millis(50))? {
+            continue;
+        }
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press {
+                match key.code {
+                    KeyCode::Up => selected = selected.saturating_sub(1),
+                    KeyCode::Down => selected = (selected + 1).min(files.len().saturating_sub(1)),
+                    KeyCode::Right | KeyCode::Enter if !files.is_empty() => {
+                        return Ok(Some(selected));
+                    }
+                    KeyCode::Left | KeyCode::Esc => return Ok(None),
+                    _ => {}
+                }
+            }
+        }
+    }
 }
 
+/// Browses the nodes of the selected file
+/// # Arguments
+/// * `file_data` - Contents of `state.selected_file`, or of stdin when it is [`STDIN`]
+/// # Returns
+/// `None` when the user leaves the file, or `Some(Err(..))` when it couldn't be parsed
 pub fn terminal_draw_loop(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     mut state: AppState,
+    file_data: Vec<u8>,
 ) -> Option<Result<(), Box<dyn Error>>> {
-    let (_bin_path, file) = current_bin_path(&state.selected_file).unwrap();
-    #[allow(unsafe_code)]
-    let mmap = unsafe { Mmap::map(&file).unwrap() };
-    let fxr_file_bytes = &mmap.as_bytes();
-
-    // Parse the file
-    let root_tree = build(fxr_file_bytes).unwrap();
-    let root_tree_clone = root_tree.clone();
+    let mut editor = Editor::new(state.selected_file.clone(), file_data);
+
+    // Parse the file. `outline` is the tree being shown, which is `full` unless a search filters
+    // it.
+    let (mut full, skipped) = match build(&editor.bytes, state.lenient) {
+        Ok(built) => built,
+        Err(err) => return Some(Err(err)),
+    };
+    let mut outline = full.clone();
+    let mut root_tree = outline.tree_items("");
+    let mut search = Search::default();
+    let mut built_revision = editor.revision;
+    let mut validation = editor.validation();
+    let mut prompt: Option<Prompt> = None;
+    let mut message = match skipped.as_slice() {
+        [] => String::new(),
+        skipped => format!("Opened leniently, left out {}", skipped.join("; ")),
+    };
+    let mut confirm_quit = false;
 
     // Initialize TreeState
     state.tree_state.toggle(vec![0]); // Expand the root node
 
     let mut last_key_time = Instant::now(); // Track the last key press time
+    let mut shown = state.tree_state.selected();
 
     // Render the UI
     loop {
+        let selected = outline.get(&state.tree_state.selected());
         terminal
             .draw(|f: &mut ratatui::Frame<'_>| {
-                let size = f.area();
+                let rows = ratatui::layout::Layout::default()
+                    .direction(ratatui::layout::Direction::Vertical)
+                    .constraints([
+                        ratatui::layout::Constraint::Min(0),
+                        ratatui::layout::Constraint::Length(1),
+                    ])
+                    .split(f.area());
                 let chunks = ratatui::layout::Layout::default()
                     .direction(ratatui::layout::Direction::Horizontal)
                     .constraints(
                         [
-                            ratatui::layout::Constraint::Percentage(100), // Full width for the tree
+                            ratatui::layout::Constraint::Percentage(40),
+                            ratatui::layout::Constraint::Percentage(60),
                         ]
                         .as_ref(),
                     )
-                    .split(size);
+                    .split(rows[0]);
 
-                let tree_widget = Tree::new(vec![root_tree_clone.clone()])
-                    .block(Block::default().borders(Borders::ALL).title("Nodes"))
+                let tree_widget = Tree::new(root_tree.clone())
+                    .block(pane_block("Nodes", state.focus == Pane::Tree))
                     .highlight_style(HIGHLIGHT_STYLE);
                 f.render_stateful_widget(tree_widget, chunks[0], &mut state.tree_state);
+
+                // Fields get an inspector under the hex dump
+                let inspected = inspector::inspected(selected);
+                let right = ratatui::layout::Layout::default()
+                    .direction(ratatui::layout::Direction::Vertical)
+                    .constraints([
+                        ratatui::layout::Constraint::Min(0),
+                        ratatui::layout::Constraint::Length(match inspected {
+                            Some(_) => inspector::HEIGHT,
+                            None => 0,
+                        }),
+                    ])
+                    .split(chunks[1]);
+                state
+                    .hex_view
+                    .render(f, right[0], &editor, selected, state.focus == Pane::Hex);
+                if let Some((node, field)) = inspected {
+                    inspector::render(f, right[1], &editor.bytes, node, field);
+                }
+                f.render_widget(
+                    status_line(
+                        &editor,
+                        &validation,
+                        prompt.as_ref(),
+                        &message,
+                        &search,
+                        &state.tree_state.selected(),
+                    ),
+                    rows[1],
+                );
             })
             .ok()?;
 
         // Handle input events
+        let mut view_changed = false;
         if event::poll(Duration::from_millis(149)).ok()? {
             if let Event::Key(key) = event::read().ok()? {
-                if last_key_time.elapsed() >= Duration::from_millis(150) {
+                if let Some(active) = prompt.take() {
+                    let searching = matches!(active, Prompt::Search { .. });
+                    let go_to = match (&active, key.code, key.kind) {
+                        (Prompt::GoTo { text }, KeyCode::Enter, KeyEventKind::Press) => {
+                            Some(text.clone())
+                        }
+                        _ => None,
+                    };
+                    // Typing isn't debounced, so fast typists don't lose characters
+                    prompt = match key.kind {
+                        KeyEventKind::Press => {
+                            prompt_key(key.code, active, &mut editor, &mut message)
+                        }
+                        _ => Some(active),
+                    };
+                    // Searching is incremental: every keystroke moves to the first match
+                    match &prompt {
+                        Some(Prompt::Search { text }) if *text != search.query => {
+                            search.query = text.clone();
+                            outline = show_outline(&full, &outline, &mut search, &mut state, true);
+                            view_changed = true;
+                        }
+                        None if searching && key.code == KeyCode::Esc => {
+                            search.query.clear();
+                            outline = show_outline(&full, &outline, &mut search, &mut state, false);
+                            view_changed = true;
+                        }
+                        _ => {}
+                    }
+                    if let Some(text) = go_to {
+                        match parse_offset(&text, editor.bytes.len()) {
+                            Ok(offset) => match full.owner(offset) {
+                                Some(id) => {
+                                    // The owner may be hidden by the search filter
+                                    if search.filter && search.is_active() {
+                                        search.filter = false;
+                                        outline = show_outline(
+                                            &full,
+                                            &outline,
+                                            &mut search,
+                                            &mut state,
+                                            false,
+                                        );
+                                        view_changed = true;
+                                    }
+                                    let owner = parse_fxr(&editor.bytes)
+                                        .ok()
+                                        .and_then(|fxr| describe_offset(&fxr, offset))
+                                        .or_else(|| full.get(&id).map(|node| node.label.clone()))
+                                        .unwrap_or_default();
+                                    message = format!("0x{:X} is in {}", offset, owner);
+                                    select_node(&mut state, id.clone());
+                                    state.hex_view.show(&(offset..offset + 1));
+                                    shown = id;
+                                }
+                                None => {
+                                    message = format!("No parsed structure owns 0x{:X}", offset)
+                                }
+                            },
+                            Err(err) => {
+                                message = err;
+                                prompt = Some(Prompt::GoTo { text });
+                            }
+                        }
+                    }
+                } else if last_key_time.elapsed() >= Duration::from_millis(150) {
                     // Debounce threshold
                     last_key_time = Instant::now(); // Update the last key press time
-                    match key.code {
-                        KeyCode::Char('q') | KeyCode::Esc => {
-                            // Exit the loop and propagate None
-                            return None;
+                    let quitting = matches!(key.code, KeyCode::Char('q') | KeyCode::Esc);
+                    match (key.code, state.focus) {
+                        (KeyCode::Char('q') | KeyCode::Esc, _) => {
+                            if !editor.is_dirty() || confirm_quit {
+                                // Exit the loop and propagate None
+                                return None;
+                            }
+                            message = "Unsaved changes, press q again to discard them".into();
+                        }
+                        (KeyCode::Char('s'), _)
+                            if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                        {
+                            message = saved_message(editor.save(), &editor);
                         }
-                        KeyCode::Up => {
-                            state.tree_state.key_up(&[root_tree.clone()]);
+                        (KeyCode::Char('z'), _)
+                            if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                        {
+                            message = match editor.undo() {
+                                Ok(Some(change)) => format!("Undid {}", change.path),
+                                Ok(None) => "Nothing to undo".into(),
+                                Err(err) => format!("Can't undo: {err}"),
+                            };
                         }
-                        KeyCode::Down => {
-                            state.tree_state.key_down(&[root_tree.clone()]);
+                        (KeyCode::Char('y'), _)
+                            if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                        {
+                            message = match editor.redo() {
+                                Ok(Some(change)) => format!("Redid {}", change.path),
+                                Ok(None) => "Nothing to redo".into(),
+                                Err(err) => format!("Can't redo: {err}"),
+                            };
                         }
-                        KeyCode::Left => {
+                        (KeyCode::Char('p'), _) => {
+                            let mut text = match editor.is_stdin() {
+                                true => "stdin".into(),
+                                false => editor.save_path().into_os_string(),
+                            };
+                            text.push(".patch.json");
+                            prompt = Some(Prompt::ExportPatch {
+                                text: PathBuf::from(text).display().to_string(),
+                            });
+                            message.clear();
+                        }
+                        (KeyCode::Char('/'), _) => {
+                            prompt = Some(Prompt::Search {
+                                text: search.query.clone(),
+                            });
+                            message.clear();
+                        }
+                        (KeyCode::Char('g'), _) => {
+                            prompt = Some(Prompt::GoTo { text: "0x".into() });
+                            message.clear();
+                        }
+                        (KeyCode::Char('n'), _) => {
+                            if let Some(id) = search.next(&state.tree_state.selected()) {
+                                select_node(&mut state, id.clone());
+                            }
+                        }
+                        (KeyCode::Char('N'), _) => {
+                            if let Some(id) = search.previous(&state.tree_state.selected()) {
+                                select_node(&mut state, id.clone());
+                            }
+                        }
+                        (KeyCode::Char('f'), _) if search.is_active() => {
+                            search.filter = !search.filter;
+                            outline = show_outline(&full, &outline, &mut search, &mut state, false);
+                            view_changed = true;
+                        }
+                        (KeyCode::F(2), _) => {
+                            prompt = Some(Prompt::SaveAs {
+                                text: editor.save_path().display().to_string(),
+                            });
+                            message.clear();
+                        }
+                        (KeyCode::Char('e') | KeyCode::Enter, _) => {
+                            if let Some((node, field)) = inspector::inspected(selected) {
+                                let raw = &editor.bytes[node.range.clone()];
+                                let interpretation = Interpretation::guess(field, raw);
+                                prompt = Some(Prompt::Value {
+                                    field,
+                                    range: node.range.clone(),
+                                    interpretation,
+                                    text: interpretation.format(raw, field.ty),
+                                });
+                                message.clear();
+                            }
+                        }
+                        (KeyCode::Tab, Pane::Tree) => state.focus = Pane::Hex,
+                        (KeyCode::Tab, Pane::Hex) => state.focus = Pane::Tree,
+                        (KeyCode::Up, Pane::Tree) => {
+                            state.tree_state.key_up(&root_tree);
+                        }
+                        (KeyCode::Down, Pane::Tree) => {
+                            state.tree_state.key_down(&root_tree);
+                        }
+                        (KeyCode::Left, Pane::Tree) => {
                             state.tree_state.key_left();
                         }
-                        KeyCode::Right => {
+                        (KeyCode::Right, Pane::Tree) => {
                             state.tree_state.key_right();
                         }
+                        (code, Pane::Hex) => {
+                            let page = (state.hex_view.page_rows() * 16) as isize;
+                            let delta = match code {
+                                KeyCode::Left => -1,
+                                KeyCode::Right => 1,
+                                KeyCode::Up => -16,
+                                KeyCode::Down => 16,
+                                KeyCode::PageUp => -page,
+                                KeyCode::PageDown => page,
+                                _ => 0,
+                            };
+                            if delta != 0 {
+                                state.hex_view.move_cursor(delta, editor.bytes.len());
+                                if let Some(owner) = outline.owner(state.hex_view.cursor) {
+                                    select_node(&mut state, owner.clone());
+                                    shown = owner;
+                                }
+                            }
+                        }
                         _ => {}
                     }
+                    confirm_quit = quitting && editor.is_dirty();
                 }
             }
         }
-    }
-}
-
-fn build<'a>(fxr_file_bytes: &&'a [u8]) -> Result<TreeItem<'a>, Box<dyn Error>> {
-    let fxr = parse_fxr(fxr_file_bytes).unwrap();
 
-    // Build reflection trees for the header and sections
-    let header = fxr.header.deref();
-    let header_tree: TreeItem = build_reflection_tree(header, get_class_name(header)).unwrap();
-    let mut children = vec![header_tree];
-
-    let section1_tree = build_section_1_tree(&fxr);
-    let section4_tree = build_section_4_tree(&fxr);
-
-    // Add parsed sections to the tree
-    if let Ok(Some(section_tree)) = section1_tree {
-        children.push(section_tree);
-    }
-    if let Ok(Some(section_tree)) = section4_tree {
-        children.push(section_tree);
-    }
+        // Relabel the tree and re-validate after an edit. An edit that breaks parsing keeps the
+        // shape of the last tree that parsed, and the status bar shows the parse error.
+        if editor.revision != built_revision {
+            built_revision = editor.revision;
+            validation = editor.validation();
+            match build(&editor.bytes, state.lenient) {
+                Ok((rebuilt, _)) => full = rebuilt,
+                Err(_) => relabel_fields(&mut full, &editor.bytes),
+            }
+            editor.mark_changes(&mut full);
+            outline = show_outline(&full, &outline, &mut search, &mut state, false);
+            view_changed = true;
+        }
+        if view_changed {
+            root_tree = outline.tree_items(&search.query);
+        }
 
-    // Add Section12, Section13, and Section14 entries to the tree
-    if let Some(section12_entries) = &fxr.section12_entries {
-        let mut section12_tree = TreeItem::new("Section12", vec![]);
-        section12_entries.deref().iter().for_each(|entry| {
-            if let Ok(child) = build_reflection_tree(entry, get_class_name(entry)) {
-                section12_tree.add_child(child);
+        // Scroll the hex pane to whatever the tree cursor moved to
+        let selected_id = state.tree_state.selected();
+        if selected_id != shown {
+            if let Some(node) = outline.get(&selected_id) {
+                state.hex_view.show(&node.range);
             }
-        });
-        children.push(section12_tree);
+            shown = selected_id;
+        }
     }
+}
 
-    if let Some(section13_entries) = &fxr.section13_entries.as_deref() {
-        let mut section13_tree = TreeItem::new("Section13", vec![]);
-        section13_entries.iter().for_each(|entry| {
-            if let Ok(child) = build_reflection_tree(entry, get_class_name(entry)) {
-                section13_tree.add_child(child);
+/// The tree to show: all of `full`, or only the branches leading to search matches while the
+/// search filters. The selection stays on the same node when it is still shown; with `jump`, or
+/// when it was filtered out, it moves to the next match instead.
+fn show_outline(
+    full: &Outline,
+    previous: &Outline,
+    search: &mut Search,
+    state: &mut AppState,
+    jump: bool,
+) -> Outline {
+    let selected = state.tree_state.selected();
+    let filtering = search.filter && search.is_active();
+    let outline = if filtering {
+        full.filtered(&search.query)
+    } else {
+        full.clone()
+    };
+    search.update(&outline);
+
+    let same = previous
+        .get(&selected)
+        .and_then(|node| outline.find(selected.len(), node.kind, &node.range));
+    // Index paths only carry over between two unfiltered trees
+    if filtering || search.shown_filtered {
+        state.tree_state.close_all();
+        state.tree_state.open(vec![0]);
+        if filtering {
+            for id in &search.matches {
+                for depth in 1..id.len() {
+                    state.tree_state.open(id[..depth].to_vec());
+                }
             }
-        });
-        children.push(section13_tree);
+        }
     }
+    search.shown_filtered = filtering;
+
+    let from = same.clone().unwrap_or(selected);
+    let target = match same {
+        Some(id) if !jump => Some(id),
+        _ => search.first_from(&from).cloned(),
+    };
+    select_node(state, target.unwrap_or_else(|| vec![0]));
+    outline
+}
 
-    if let Some(section14_entries) = &fxr.section14_entries.as_deref() {
-        let mut section14_tree = TreeItem::new("Section14", vec![]);
-        section14_entries.iter().for_each(|entry| {
-            if let Ok(child) = build_reflection_tree(entry, get_class_name(entry)) {
-                section14_tree.add_child(child);
+/// Handles a key typed into the status bar prompt
+/// # Returns
+/// The prompt to keep showing, or `None` once it is submitted or dismissed
+fn prompt_key(
+    code: KeyCode,
+    prompt: Prompt,
+    editor: &mut Editor,
+    message: &mut String,
+) -> Option<Prompt> {
+    match (code, prompt) {
+        (KeyCode::Esc, _) => None,
+        (
+            KeyCode::Tab,
+            Prompt::Value {
+                field,
+                range,
+                interpretation,
+                ..
+            },
+        ) => {
+            let interpretation = interpretation.next(field.ty);
+            let text = interpretation.format(&editor.bytes[range.clone()], field.ty);
+            Some(Prompt::Value {
+                field,
+                range,
+                interpretation,
+                text,
+            })
+        }
+        (KeyCode::Backspace, mut prompt) => {
+            prompt.text_mut().pop();
+            Some(prompt)
+        }
+        (KeyCode::Char(c), mut prompt) => {
+            prompt.text_mut().push(c);
+            Some(prompt)
+        }
+        (
+            KeyCode::Enter,
+            Prompt::Value {
+                field,
+                range,
+                interpretation,
+                text,
+            },
+        ) => match interpretation.encode(&text, field.ty).and_then(|new| {
+            editor
+                .write(field, range.start, &new)
+                .map_err(|err| err.to_string())
+        }) {
+            Ok(()) => {
+                *message = format!("{}.{} = {}", field.section, field.name, text.trim());
+                None
+            }
+            Err(err) => {
+                *message = err;
+                Some(Prompt::Value {
+                    field,
+                    range,
+                    interpretation,
+                    text,
+                })
             }
-        });
-        children.push(section14_tree);
+        },
+        (KeyCode::Enter, Prompt::SaveAs { text }) => {
+            *message = saved_message(editor.save_as(PathBuf::from(text.trim())), editor);
+            None
+        }
+        (KeyCode::Enter, Prompt::Search { .. } | Prompt::GoTo { .. }) => None,
+        (KeyCode::Enter, Prompt::ExportPatch { text }) => {
+            let path = PathBuf::from(text.trim());
+            *message = match editor.export_patch(&path) {
+                Ok(count) => format!("Exported {} change(s) to {}", count, path.display()),
+                Err(err) => format!("Export failed: {}", err),
+            };
+            None
+        }
+        (_, prompt) => Some(prompt),
     }
+}
 
-    // Combine the trees into a single root
-    Ok(TreeItem::new("FXR File", children))
+/// Reads an offset typed as `0x1458` or `5208`
+/// # Arguments
+/// * `text` - The typed offset
+/// * `len` - Length of the file, which the offset must be inside
+fn parse_offset(text: &str, len: usize) -> Result<usize, String> {
+    let text = text.trim();
+    let offset = match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(digits) => usize::from_str_radix(digits, 16),
+        None => text.parse(),
+    }
+    .map_err(|_| format!("`{}` is not an offset", text))?;
+    if offset >= len {
+        return Err(format!(
+            "0x{:X} is past the end of the file (0x{:X} bytes)",
+            offset, len
+        ));
+    }
+    Ok(offset)
 }
 
-fn build_section_4_tree<'a>(fxr: &ParsedFXR<'a>) -> Result<Option<TreeItem<'a>>, Box<dyn Error>> {
-    if let Some(section4_tree) = &fxr.section4_tree {
-        let section4: &Section4Container = section4_tree.container.deref();
-        let mut section_tree: TreeItem = build_reflection_tree(section4, get_class_name(section4))?;
-
-        if let Some(section4_entries) = &section4_tree.section4_entries {
-            section4_entries.deref().iter().for_each(|section4_entry| {
-                if let Ok(child) =
-                    build_reflection_tree(section4_entry, get_class_name(section4_entry))
-                {
-                    section_tree.add_child(child);
-                }
-            });
-        }
+fn saved_message(result: io::Result<Option<PathBuf>>, editor: &Editor) -> String {
+    match result {
+        Ok(Some(backup)) => format!(
+            "Saved {} (backup in {})",
+            editor.path.display(),
+            backup.display()
+        ),
+        Ok(None) => format!("Saved {}", editor.path.display()),
+        Err(err) => format!("Save failed: {}", err),
+    }
+}
 
-        if let Some(section5_entries) = &section4_tree.section5_entries {
-            section5_entries.deref().iter().for_each(|section5_entry| {
-                if let Ok(child) =
-                    build_reflection_tree(section5_entry, get_class_name(section5_entry))
-                {
-                    section_tree.add_child(child);
+/// Bottom line: the prompt being typed, or the file's state and the editing keys
+fn status_line<'a>(
+    editor: &Editor,
+    validation: &Result<(), String>,
+    prompt: Option<&Prompt>,
+    message: &str,
+    search: &Search,
+    selected: &[usize],
+) -> Line<'a> {
+    let red = Style::default().fg(Color::Red);
+    let green = Style::default().fg(Color::Green);
+    let mut spans = match prompt {
+        Some(Prompt::Value {
+            field,
+            range,
+            interpretation,
+            text,
+        }) => {
+            let mut spans = vec![
+                Span::styled(
+                    format!(
+                        " {}.{} as {:?}: ",
+                        field.section, field.name, interpretation
+                    ),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::raw(format!("{}_ ", text)),
+            ];
+            spans.push(match interpretation.encode(text, field.ty) {
+                Ok(new) if editor.check_field(*field, range, &new) => {
+                    Span::styled("passes its rule", green)
                 }
+                Ok(_) => Span::styled("fails its rule", red),
+                Err(err) => Span::styled(err, red),
             });
+            spans.push(Span::raw(
+                "  (Tab: hex/int/float, Enter: apply, Esc: cancel)",
+            ));
+            spans
         }
-
-        if let Some(section6_entries) = &section4_tree.section6_entries {
-            section6_entries.deref().iter().for_each(|section6_entry| {
-                if let Ok(child) =
-                    build_reflection_tree(section6_entry, get_class_name(section6_entry))
-                {
-                    section_tree.add_child(child);
-                }
-            });
+        Some(Prompt::SaveAs { text }) => vec![
+            Span::styled(" Save as: ", Style::default().fg(Color::Cyan)),
+            Span::raw(format!("{}_", text)),
+        ],
+        Some(Prompt::ExportPatch { text }) => vec![
+            Span::styled(" Export patch to: ", Style::default().fg(Color::Cyan)),
+            Span::raw(format!("{}_", text)),
+        ],
+        Some(Prompt::Search { text }) => vec![
+            Span::styled(" /", Style::default().fg(Color::Cyan)),
+            Span::raw(format!("{}_ ", text)),
+            match search.matches.len() {
+                0 if search.is_active() => Span::styled("no matches", red),
+                count => Span::raw(format!("{} matches", count)),
+            },
+            Span::raw("  (Enter: keep, Esc: clear)"),
+        ],
+        Some(Prompt::GoTo { text }) => vec![
+            Span::styled(" Go to offset: ", Style::default().fg(Color::Cyan)),
+            Span::raw(format!("{}_", text)),
+            Span::raw("  (0x for hex, Enter: go, Esc: cancel)"),
+        ],
+        None => {
+            let mut spans = vec![
+                Span::raw(format!(
+                    " {}{} ",
+                    editor.path.display(),
+                    if editor.is_dirty() { " *" } else { "" }
+                )),
+                match validation {
+                    Ok(()) => Span::styled("valid", green),
+                    Err(err) => Span::styled(err.clone(), red),
+                },
+            ];
+            if search.is_active() {
+                spans.push(Span::styled(
+                    format!(
+                        " | /{} {}{}",
+                        search.query,
+                        search.position(selected),
+                        if search.filter { " filtered" } else { "" }
+                    ),
+                    Style::default().fg(Color::Yellow),
+                ));
+            }
+            if !message.is_empty() {
+                spans.push(Span::raw(format!(" | {}", message)));
+            }
+            spans.push(Span::styled(
+                " | e: edit  Ctrl+Z/Y: undo/redo  p: patch  Ctrl+S: save  F2: save as  \
+                 /: search  n/N: next/previous  f: filter  g: go to offset",
+                Style::default().fg(Color::DarkGray),
+            ));
+            spans
         }
+    };
+    if prompt.is_some() && !message.is_empty() {
+        spans.push(Span::styled(format!(" | {}", message), red));
+    }
+    Line::from(spans)
+}
 
-        Ok(Some(section_tree))
-    } else {
-        Ok(None)
+/// Selects a node in the tree, opening its ancestors so it is visible
+fn select_node(state: &mut AppState, id: Vec<usize>) {
+    for depth in 1..id.len() {
+        state.tree_state.open(id[..depth].to_vec());
     }
+    state.tree_state.select(id);
 }
 
-fn build_section_1_tree<'a>(fxr: &ParsedFXR<'a>) -> Result<Option<TreeItem<'a>>, Box<dyn Error>> {
-    if let Some(section1_tree) = &fxr.section1_tree {
-        let section1 = section1_tree.section1.deref();
-        let mut section_tree: TreeItem = build_reflection_tree(section1, get_class_name(section1))?;
-        if let Some(section2) = &section1_tree.section2.as_deref() {
-            let section2_tree: TreeItem =
-                build_reflection_tree(section2, get_class_name(section2))?;
-            section_tree.add_child(section2_tree);
-        }
-        if let Some(section3) = &section1_tree.section3 {
-            section3.deref().iter().for_each(|section_3_entry| {
-                if let Ok(child) =
-                    build_reflection_tree(section_3_entry, get_class_name(section_3_entry))
-                {
-                    section_tree.add_child(child);
-                }
-            });
-        }
-        Ok(Some(section_tree))
+/// Bordered pane whose border is highlighted while it has the focus
+fn pane_block<'a>(title: impl Into<ratatui::text::Line<'a>>, focused: bool) -> Block<'a> {
+    let block = Block::default().borders(Borders::ALL).title(title);
+    if focused {
+        block.border_style(Style::default().fg(ratatui::style::Color::Yellow))
     } else {
-        Ok(None)
+        block
     }
 }
 
-pub fn current_bin_path(selected_file: &PathBuf) -> Result<(PathBuf, File), Box<dyn Error>> {
-    let current_dir = env::current_dir()?;
-    let bin_path = current_dir.join(selected_file);
-    let file = File::open(&bin_path)?;
-    Ok((bin_path, file))
+/// Parses the file into the tree shown
+/// # Returns
+/// The tree, and with `lenient` the problems that left sections out of it
+fn build(fxr_file_bytes: &[u8], lenient: bool) -> Result<(Outline, Vec<String>), Box<dyn Error>> {
+    let (fxr, skipped) = if lenient {
+        parse_fxr_lenient(fxr_file_bytes)?
+    } else {
+        (parse_fxr(fxr_file_bytes)?, Vec::new())
+    };
+    Ok((build_outline(&fxr, fxr_file_bytes.len()), skipped))
 }
@@ -0,0 +1,322 @@
+use super::search::Pattern;
+use ratatui::style::{Color, Style};
+use ratatui_tree_widget::TreeItem;
+use reader::fxr::reflect::FieldType;
+use std::{cmp::Reverse, collections::BTreeSet, ops::Range};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    /// A label grouping other nodes, such as the file root or `Section12`
+    Group,
+    Struct,
+    Field,
+}
+
+/// Which struct field a `NodeKind::Field` node shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldRef {
+    /// Struct name, e.g. `Section6Entry`
+    pub section: &'static str,
+    pub name: &'static str,
+    pub ty: FieldType,
+    /// Absolute offset of the struct the field belongs to
+    pub struct_offset: usize,
+}
+
+/// One node of the tree shown in the TUI, along with the bytes of the file it covers
+#[derive(Debug, Clone)]
+pub struct Node {
+    pub label: String,
+    pub kind: NodeKind,
+    /// Absolute byte range in the file
+    pub range: Range<usize>,
+    pub field: Option<FieldRef>,
+    /// Struct name of a `NodeKind::Struct` node, e.g. `Section9Entry`
+    pub section: Option<&'static str>,
+    /// Value of a `NodeKind::Field` node, read as an unsigned integer
+    pub value: Option<u64>,
+    pub children: Vec<Node>,
+}
+
+impl Node {
+    pub fn new(label: impl Into<String>, kind: NodeKind, range: Range<usize>) -> Self {
+        Self {
+            label: label.into(),
+            kind,
+            range,
+            field: None,
+            section: None,
+            value: None,
+            children: Vec::new(),
+        }
+    }
+
+    /// A struct of the file, whose fields are added as children
+    pub fn section(label: impl Into<String>, range: Range<usize>, section: &'static str) -> Self {
+        Self {
+            section: Some(section),
+            ..Self::new(label, NodeKind::Struct, range)
+        }
+    }
+
+    /// A leaf showing one field of a struct
+    pub fn field(
+        label: impl Into<String>,
+        range: Range<usize>,
+        field: FieldRef,
+        value: u64,
+    ) -> Self {
+        Self {
+            field: Some(field),
+            value: Some(value),
+            ..Self::new(label, NodeKind::Field, range)
+        }
+    }
+
+    /// A group spanning all of its children
+    pub fn group(label: impl Into<String>, children: Vec<Node>) -> Self {
+        let start = children.iter().map(|c| c.range.start).min().unwrap_or(0);
+        let end = children.iter().map(|c| c.range.end).max().unwrap_or(0);
+        Self {
+            children,
+            ..Self::new(label, NodeKind::Group, start..end)
+        }
+    }
+
+    pub fn add_child(&mut self, child: Node) {
+        self.children.push(child);
+    }
+
+    fn matches(&self, pattern: &Pattern) -> bool {
+        pattern.matches(self)
+    }
+
+    fn to_tree_item(&self, query: &Pattern) -> TreeItem<'static> {
+        let children: Vec<TreeItem> = self
+            .children
+            .iter()
+            .map(|child| child.to_tree_item(query))
+            .collect();
+        let item = if children.is_empty() {
+            TreeItem::new_leaf(self.label.clone())
+        } else {
+            TreeItem::new(self.label.clone(), children)
+        };
+        if self.matches(query) {
+            item.style(Style::default().fg(Color::Yellow))
+        } else {
+            item
+        }
+    }
+
+    /// The node with only the branches leading to a match. A matching node keeps all of its
+    /// children, so a matching struct still shows its fields.
+    fn filtered(&self, query: &Pattern) -> Option<Node> {
+        if self.matches(query) {
+            return Some(self.clone());
+        }
+        let children: Vec<Node> = self
+            .children
+            .iter()
+            .filter_map(|child| child.filtered(query))
+            .collect();
+        if children.is_empty() {
+            return None;
+        }
+        Some(Node {
+            label: self.label.clone(),
+            kind: self.kind,
+            range: self.range.clone(),
+            field: self.field,
+            section: self.section,
+            value: self.value,
+            children,
+        })
+    }
+}
+
+/// The nodes of the tree, addressed by the same index paths `TreeState` uses
+#[derive(Debug, Clone, Default)]
+pub struct Outline {
+    pub roots: Vec<Node>,
+    /// Owner of every byte, built with the tree. Edits only relabel nodes, so it stays valid
+    /// until the tree is rebuilt.
+    index: OffsetIndex,
+}
+
+impl Outline {
+    pub fn new(roots: Vec<Node>) -> Self {
+        let index = OffsetIndex::new(&roots);
+        Self { roots, index }
+    }
+
+    /// Items for the tree widget, with the nodes matching `query` highlighted
+    pub fn tree_items(&self, query: &str) -> Vec<TreeItem<'static>> {
+        let query = Pattern::parse(query);
+        self.roots
+            .iter()
+            .map(|root| root.to_tree_item(&query))
+            .collect()
+    }
+
+    /// Index paths of every node matching `query`, see [`Pattern::parse`], in tree order
+    pub fn search(&self, query: &str) -> Vec<Vec<usize>> {
+        fn search(
+            nodes: &[Node],
+            query: &Pattern,
+            path: &mut Vec<usize>,
+            found: &mut Vec<Vec<usize>>,
+        ) {
+            for (i, node) in nodes.iter().enumerate() {
+                path.push(i);
+                if node.matches(query) {
+                    found.push(path.clone());
+                }
+                search(&node.children, query, path, found);
+                path.pop();
+            }
+        }
+
+        let mut found = Vec::new();
+        search(
+            &self.roots,
+            &Pattern::parse(query),
+            &mut Vec::new(),
+            &mut found,
+        );
+        found
+    }
+
+    /// The tree without the nodes that neither match `query` nor lead to a match
+    pub fn filtered(&self, query: &str) -> Outline {
+        let query = Pattern::parse(query);
+        Outline::new(
+            self.roots
+                .iter()
+                .filter_map(|root| root.filtered(&query))
+                .collect(),
+        )
+    }
+
+    /// Index path of the node of `kind` covering exactly `range` at `depth`, so a selection
+    /// survives the tree being rebuilt or filtered
+    pub fn find(&self, depth: usize, kind: NodeKind, range: &Range<usize>) -> Option<Vec<usize>> {
+        fn find(
+            nodes: &[Node],
+            depth: usize,
+            kind: NodeKind,
+            range: &Range<usize>,
+            path: &mut Vec<usize>,
+        ) -> bool {
+            for (i, node) in nodes.iter().enumerate() {
+                path.push(i);
+                let found = if path.len() == depth {
+                    node.kind == kind && node.range == *range
+                } else {
+                    find(&node.children, depth, kind, range, path)
+                };
+                if found {
+                    return true;
+                }
+                path.pop();
+            }
+            false
+        }
+
+        let mut path = Vec::new();
+        find(&self.roots, depth, kind, range, &mut path).then_some(path)
+    }
+
+    /// The node at an index path such as `[0, 2, 1]`
+    pub fn get(&self, id: &[usize]) -> Option<&Node> {
+        let (first, rest) = id.split_first()?;
+        rest.iter()
+            .try_fold(self.roots.get(*first)?, |node, &i| node.children.get(i))
+    }
+
+    /// Index path of the innermost struct or field covering an absolute offset
+    pub fn owner(&self, offset: usize) -> Option<Vec<usize>> {
+        self.index.owner(offset).map(<[usize]>::to_vec)
+    }
+}
+
+/// The innermost struct or field owning each byte of the file, as sorted runs of bytes that
+/// share an owner.
+///
+/// Nested structs live wherever their offsets point rather than inside their parent's bytes, so
+/// ranges from every level of the tree overlap. They are resolved once when the tree is built:
+/// the deepest node wins, and between nodes of the same depth the first in tree order.
+#[derive(Debug, Clone, Default)]
+struct OffsetIndex {
+    runs: Vec<(Range<usize>, Vec<usize>)>,
+}
+
+impl OffsetIndex {
+    fn new(roots: &[Node]) -> Self {
+        fn collect<'a>(
+            nodes: &'a [Node],
+            path: &mut Vec<usize>,
+            owners: &mut Vec<(&'a Range<usize>, Vec<usize>)>,
+        ) {
+            for (i, node) in nodes.iter().enumerate() {
+                path.push(i);
+                if node.kind != NodeKind::Group && !node.range.is_empty() {
+                    owners.push((&node.range, path.clone()));
+                }
+                collect(&node.children, path, owners);
+                path.pop();
+            }
+        }
+
+        // Pre-order, so a lower index means earlier in the tree
+        let mut owners = Vec::new();
+        collect(roots, &mut Vec::new(), &mut owners);
+
+        let mut bounds: Vec<usize> = owners
+            .iter()
+            .flat_map(|(range, _)| [range.start, range.end])
+            .collect();
+        bounds.sort_unstable();
+        bounds.dedup();
+        let mut starts: Vec<usize> = (0..owners.len()).collect();
+        starts.sort_by_key(|&i| owners[i].0.start);
+        let mut ends = starts.clone();
+        ends.sort_by_key(|&i| owners[i].0.end);
+
+        // Sweep the boundaries, keeping the nodes covering the current run ordered so the
+        // winner is the last one
+        let mut covering = BTreeSet::new();
+        let (mut next_start, mut next_end) = (0, 0);
+        let mut runs: Vec<(Range<usize>, Vec<usize>)> = Vec::new();
+        for pair in bounds.windows(2) {
+            let (start, end) = (pair[0], pair[1]);
+            while let Some(&i) = starts
+                .get(next_start)
+                .filter(|&&i| owners[i].0.start == start)
+            {
+                covering.insert((owners[i].1.len(), Reverse(i)));
+                next_start += 1;
+            }
+            while let Some(&i) = ends.get(next_end).filter(|&&i| owners[i].0.end == start) {
+                covering.remove(&(owners[i].1.len(), Reverse(i)));
+                next_end += 1;
+            }
+            let Some(&(_, Reverse(i))) = covering.last() else {
+                continue;
+            };
+            match runs.last_mut() {
+                Some((run, id)) if run.end == start && *id == owners[i].1 => run.end = end,
+                _ => runs.push((start..end, owners[i].1.clone())),
+            }
+        }
+        Self { runs }
+    }
+
+    fn owner(&self, offset: usize) -> Option<&[usize]> {
+        let i = self.runs.partition_point(|(run, _)| run.end <= offset);
+        self.runs
+            .get(i)
+            .filter(|(run, _)| run.contains(&offset))
+            .map(|(_, id)| id.as_slice())
+    }
+}
@@ -0,0 +1,187 @@
+use super::outline::{Node, NodeKind, Outline};
+
+/// Text searched for with `/`, and the nodes it matches
+#[derive(Debug, Default)]
+pub struct Search {
+    pub query: String,
+    /// Hide the nodes that neither match nor lead to a match
+    pub filter: bool,
+    /// Index paths of the matching nodes, in tree order
+    pub matches: Vec<Vec<usize>>,
+    /// Whether the tree on screen is filtered, in which case its index paths don't match the
+    /// full tree's
+    pub shown_filtered: bool,
+}
+
+impl Search {
+    pub fn is_active(&self) -> bool {
+        !self.query.is_empty()
+    }
+
+    /// Finds the matches in the tree being shown
+    pub fn update(&mut self, outline: &Outline) {
+        self.matches = if self.is_active() {
+            outline.search(&self.query)
+        } else {
+            Vec::new()
+        };
+    }
+
+    /// The first match at or after `selected` in tree order, wrapping around to the top
+    pub fn first_from(&self, selected: &[usize]) -> Option<&Vec<usize>> {
+        self.matches
+            .iter()
+            .find(|id| id.as_slice() >= selected)
+            .or(self.matches.first())
+    }
+
+    /// The match after `selected`, wrapping around to the top
+    pub fn next(&self, selected: &[usize]) -> Option<&Vec<usize>> {
+        self.matches
+            .iter()
+            .find(|id| id.as_slice() > selected)
+            .or(self.matches.first())
+    }
+
+    /// The match before `selected`, wrapping around to the bottom
+    pub fn previous(&self, selected: &[usize]) -> Option<&Vec<usize>> {
+        self.matches
+            .iter()
+            .rev()
+            .find(|id| id.as_slice() < selected)
+            .or(self.matches.last())
+    }
+
+    /// `3/12`, the 1-based position of `selected` among the matches, or `-/12` when it isn't one
+    pub fn position(&self, selected: &[usize]) -> String {
+        match self.matches.iter().position(|id| id.as_slice() == selected) {
+            Some(i) => format!("{}/{}", i + 1, self.matches.len()),
+            None => format!("-/{}", self.matches.len()),
+        }
+    }
+}
+
+/// A parsed search query
+#[derive(Debug, PartialEq)]
+pub enum Pattern {
+    /// Matches labels containing the text, ignoring case
+    Text(String),
+    /// Matches nodes satisfying every predicate
+    Predicates(Vec<Predicate>),
+}
+
+/// One `kind=Section9Entry` or `unk04>=3` term of a query
+#[derive(Debug, PartialEq)]
+pub enum Predicate {
+    /// The struct is of this kind, ignoring case
+    Kind(String),
+    /// A field with this name, ignoring case, whose value compares to `value`
+    Field { name: String, op: Op, value: u64 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Op {
+    fn compare(self, left: u64, right: u64) -> bool {
+        match self {
+            Op::Eq => left == right,
+            Op::Ne => left != right,
+            Op::Lt => left < right,
+            Op::Le => left <= right,
+            Op::Gt => left > right,
+            Op::Ge => left >= right,
+        }
+    }
+}
+
+impl Predicate {
+    /// Parses `kind=X`, `kind:X` or `name<op>value`, where `<op>` is one of `=` `==` `!=` `<`
+    /// `<=` `>` `>=` and the value is decimal or `0x` hex
+    fn parse(term: &str) -> Option<Predicate> {
+        if let Some(kind) = term
+            .strip_prefix("kind:")
+            .or_else(|| term.strip_prefix("kind="))
+        {
+            return (!kind.is_empty()).then(|| Predicate::Kind(kind.to_lowercase()));
+        }
+        let split = term.find(['=', '!', '<', '>'])?;
+        let (name, rest) = term.split_at(split);
+        let (op, value) = [
+            ("==", Op::Eq),
+            ("!=", Op::Ne),
+            ("<=", Op::Le),
+            (">=", Op::Ge),
+            ("=", Op::Eq),
+            ("<", Op::Lt),
+            (">", Op::Gt),
+        ]
+        .into_iter()
+        .find_map(|(symbol, op)| rest.strip_prefix(symbol).map(|value| (op, value)))?;
+        let value = match value
+            .strip_prefix("0x")
+            .or_else(|| value.strip_prefix("0X"))
+        {
+            Some(hex) => u64::from_str_radix(hex, 16).ok()?,
+            None => value.parse().ok()?,
+        };
+        let valid_name =
+            !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        valid_name.then(|| Predicate::Field {
+            name: name.to_lowercase(),
+            op,
+            value,
+        })
+    }
+}
+
+impl Pattern {
+    /// Parses a search query. When every whitespace-separated term is a predicate, such as
+    /// `kind=Section9Entry unk04=0` or `section11_count>=4`, nodes are matched on their struct kind
+    /// and field values; otherwise the query is text searched for in the labels.
+    ///
+    /// With a field predicate only fields match, and `kind` terms narrow them to fields of that
+    /// struct. With only `kind` terms the structs themselves match.
+    pub fn parse(query: &str) -> Pattern {
+        let predicates: Option<Vec<Predicate>> =
+            query.split_whitespace().map(Predicate::parse).collect();
+        match predicates {
+            Some(predicates) if !predicates.is_empty() => Pattern::Predicates(predicates),
+            _ => Pattern::Text(query.to_lowercase()),
+        }
+    }
+
+    pub fn matches(&self, node: &Node) -> bool {
+        match self {
+            Pattern::Text(text) => !text.is_empty() && node.label.to_lowercase().contains(text),
+            Pattern::Predicates(predicates) => {
+                let has_field = predicates
+                    .iter()
+                    .any(|p| matches!(p, Predicate::Field { .. }));
+                let kind = match (node.kind, &node.field) {
+                    (NodeKind::Field, Some(field)) if has_field => field.section,
+                    (NodeKind::Struct, _) if !has_field => match node.section {
+                        Some(section) => section,
+                        None => return false,
+                    },
+                    _ => return false,
+                };
+                predicates.iter().all(|predicate| match predicate {
+                    Predicate::Kind(wanted) => kind.eq_ignore_ascii_case(wanted),
+                    Predicate::Field { name, op, value } => {
+                        let field_name = node.field.map(|field| field.name).unwrap_or_default();
+                        field_name.eq_ignore_ascii_case(name)
+                            && node.value.is_some_and(|v| op.compare(v, *value))
+                    }
+                })
+            }
+        }
+    }
+}
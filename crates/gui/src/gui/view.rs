@@ -1,95 +1,145 @@
-use log::debug;
-use ratatui_tree_widget::TreeItem;
-use serde_reflection::{ContainerFormat, Format, Named, Samples, Tracer, TracerConfig};
-use std::{collections::BTreeMap, error::Error};
+use super::outline::{FieldRef, Node, NodeKind, Outline};
+use reader::fxr::{
+    Section11Entry,
+    fxr_parser_with_sections::ParsedFXR,
+    reflect::{Field, FieldValue},
+    walk::{self, SectionPath, is_offset_field},
+};
+use std::collections::HashMap;
 
-/// Build a reflection tree for a given type
+/// Build the tree of every struct in a parsed file, nested the way the parser follows offsets
 /// # Arguments
-/// * `sample` - A sample instance of the type to reflect
-/// * `name` - The name of the type to reflect
+/// * `fxr` - The parsed file
+/// * `file_len` - Length of the file, covered by the root node
 /// # Returns
-/// A `TreeItem` representing the reflection tree of the type
-/// # Panics
-/// Panics if the type is not found in the registry
-pub fn build_reflection_tree<T: serde::Serialize + ?Sized>(
-    sample: &T,
-    name: &str,
-) -> Result<TreeItem<'static>, Box<dyn Error>> {
-    let config = TracerConfig::default();
-    let mut tracer = Tracer::new(config);
-    let mut samples = Samples::new();
+/// An `Outline` with a single `FXR File` root. Each struct is labeled with its absolute offset and
+/// lists its fields followed by the structs it points to. Runs of array entries, such as a
+/// Section11 slice, are collected under one expandable group.
+pub fn build_outline(fxr: &ParsedFXR<'_>, file_len: usize) -> Outline {
+    let nodes = walk::walk(fxr);
+    let mut children: HashMap<Option<SectionPath>, Vec<&walk::Node>> = HashMap::new();
+    for node in &nodes {
+        children.entry(node.path.parent()).or_default().push(node);
+    }
 
-    tracer.trace_value(&mut samples, sample)?;
-    let registry: BTreeMap<String, ContainerFormat> = tracer.registry()?;
+    let mut root = Node::group("FXR File", build_children(&children, None));
+    root.range = 0..file_len;
+    Outline::new(vec![root])
+}
 
-    let struct_desc: &ContainerFormat = registry.get(name).unwrap_or_else(|| {
-        panic!(
-            "Type not found in registry: {}. Contents: {:#?}",
-            name,
-            registry
-                .keys()
-                .map(String::as_str)
-                .collect::<Vec<_>>()
-                .join(", ")
-        )
-    });
+fn build_children(
+    children: &HashMap<Option<SectionPath>, Vec<&walk::Node>>,
+    parent: Option<SectionPath>,
+) -> Vec<Node> {
+    let mut built: Vec<Node> = Vec::new();
+    // Kind of the array group at the end of `built`, if the last child went into one
+    let mut open_group: Option<&str> = None;
+    for node in children.get(&parent).into_iter().flatten() {
+        let mut section = build_section_node(node);
+        section
+            .children
+            .extend(build_children(children, Some(node.path.clone())));
 
-    let mut children_items = vec![];
+        let is_entry = node
+            .path
+            .segments()
+            .last()
+            .is_some_and(|s| s.index.is_some());
+        let kind = node.section.kind();
+        match (is_entry, open_group, built.last_mut()) {
+            (true, Some(open), Some(group)) if open == kind => {
+                group.range.start = group.range.start.min(section.range.start);
+                group.range.end = group.range.end.max(section.range.end);
+                group.children.push(section);
+            }
+            (true, _, _) => {
+                built.push(Node::group(kind, vec![section]));
+                open_group = Some(kind);
+            }
+            (false, _, _) => {
+                built.push(section);
+                open_group = None;
+            }
+        }
+    }
 
-    if let ContainerFormat::Struct(fields_vec) = struct_desc {
-        for Named {
-            name: field_name,
-            value: field_type,
-        } in fields_vec.iter()
-        {
-            // Extract the value of the field using serde_json
-            let field_value = serde_json::to_value(sample)
-                .map(|v| v.get(field_name).cloned())
-                .unwrap_or(Some(serde_json::Value::Null));
+    for group in built.iter_mut().filter(|n| n.kind == NodeKind::Group) {
+        group.label = format!(
+            "{} × {} @ 0x{:X}",
+            group.label,
+            group.children.len(),
+            group.range.start
+        );
+    }
+    built
+}
 
-            // Handle TupleArray formatting
-            let formatted_value = if let Format::TupleArray { content, size } = field_type {
-                if let Some(serde_json::Value::Array(array)) = field_value {
-                    let array_values: Vec<String> =
-                        array.iter().map(|v| format!("{:?}", v)).collect();
-                    format!(
-                        "TupleArray {{ content: {:?}, size: {} , value: Some(Array [{}]) }}",
-                        content,
-                        size,
-                        array_values.join(", ")
-                    )
-                } else {
-                    format!(
-                        "TupleArray {{ content: {:?}, size: {} , value: None }}",
-                        content, size
-                    )
-                }
-            } else {
-                format!("{:?}", field_value)
-            };
+/// Build a tree node listing every field of a section struct
+/// # Arguments
+/// * `node` - The struct to list, private `unkXX` fields included
+/// # Returns
+/// A `Node` labeled with the struct name, array index and absolute offset, with one leaf per
+/// field covering the field's bytes. Pointer fields are shown in hex, and Section11 slots show
+/// their best-guess value in the label.
+pub fn build_section_node(node: &walk::Node) -> Node {
+    let index = node
+        .path
+        .segments()
+        .last()
+        .and_then(|segment| segment.index)
+        .map(|i| format!("[{}]", i))
+        .unwrap_or_default();
+    let mut label = format!("{}{} @ 0x{:X}", node.section.kind(), index, node.offset);
+    if let Some(entry) = node.section.get::<Section11Entry>() {
+        label.push_str(&format!(" = {}", entry.value()));
+    }
 
-            // Create a child TreeItem for this field
-            let mut field_item = TreeItem::new_leaf(format!("{}: {}", field_name, formatted_value));
+    let size = node.section.as_bytes().len();
+    let mut section = Node::section(label, node.offset..node.offset + size, node.section.kind());
+    for field in node.section.reflect() {
+        let start = node.offset + field.offset;
+        section.add_child(Node::field(
+            field_label(field.name, field.value()),
+            start..start + field.bytes.len(),
+            FieldRef {
+                section: node.section.kind(),
+                name: field.name,
+                ty: field.ty,
+                struct_offset: node.offset,
+            },
+            field.value().as_u64(),
+        ));
+    }
+    section
+}
 
-            // Optional: resolve sub-structs from registry
-            if let Format::TypeName(child_type_name) = field_type {
-                if let Some(_child_type) = registry.get(child_type_name) {
-                    let child_tree = build_reflection_tree(&(), child_type_name);
-                    field_item =
-                        TreeItem::new(format!("{}: {}", field_name, formatted_value), Vec::new());
-                    field_item.add_child(child_tree?);
+/// Rewrites the label of every field from `bytes`, for when edited bytes no longer parse and the
+/// tree can't be rebuilt
+pub fn relabel_fields(outline: &mut Outline, bytes: &[u8]) {
+    fn relabel(nodes: &mut [Node], bytes: &[u8]) {
+        for node in nodes {
+            if let (Some(field), Some(raw)) = (node.field, bytes.get(node.range.clone())) {
+                let value = Field {
+                    name: field.name,
+                    offset: node.range.start - field.struct_offset,
+                    ty: field.ty,
+                    bytes: raw,
                 }
+                .value();
+                node.label = field_label(field.name, value);
+                node.value = Some(value.as_u64());
             }
-
-            children_items.push(field_item);
+            relabel(&mut node.children, bytes);
         }
-    } else {
-        panic!("Expected a struct format");
     }
+    relabel(&mut outline.roots, bytes);
+}
 
-    let debug = format!("{:#?}", struct_desc);
-    debug!("Debug TreeItem: {}", debug);
-
-    // Return the root TreeItem
-    Ok(TreeItem::new(name.to_string(), children_items))
+/// `name: value`, with offsets in hex
+fn field_label(name: &str, value: FieldValue) -> String {
+    if is_offset_field(name) {
+        format!("{}: 0x{:X}", name, value.as_u64())
+    } else {
+        format!("{}: {}", name, value)
+    }
 }
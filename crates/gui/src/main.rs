@@ -1,28 +1,95 @@
-use chrono::DateTime;
+use chrono::{DateTime, Utc};
+use clap::Parser;
 use crash_handler::{CrashContext, CrashEventResult, CrashHandler, make_crash_event};
 use crossterm::{
     event::{DisableMouseCapture, EnableMouseCapture},
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
-use ratatui::{
-    Terminal,
-    prelude::{Backend, CrosstermBackend},
-};
+use ratatui::{Terminal, prelude::CrosstermBackend};
 use ratatui_tree_widget::TreeState;
-use reader::fxr::fxr_parser_with_sections::{ParsedFXR, parse_fxr};
+use reader::fxr::{
+    archive::{self, is_bnd4, is_dcx},
+    fxr_parser_with_sections::{ParsedFXR, parse_fxr, parse_fxr_lenient},
+};
 use std::{
-    any::Any, env, error::Error, fs, io::Read, os::windows::fs::MetadataExt, path::PathBuf,
+    any::Any,
+    env,
+    error::Error,
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
     sync::Mutex,
+    time::SystemTime,
 };
 mod gui;
-use gui::{file_selection_loop, terminal_draw_loop};
+use gui::{
+    Pane, STDIN, archive_selection_loop,
+    dialog::{Recovery, error_dialog},
+    file_selection_loop,
+    hex::HexView,
+    terminal_draw_loop,
+};
 use std::{fs::File, io};
 
+/// Path and contents of a file to open
+type OpenFile = (PathBuf, Vec<u8>);
+
+/// Browse and edit `.fxr` files in the terminal
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    /// An `.fxr` file or BND4/DCX archive to open, a directory to browse, or `-` to read a file
+    /// from stdin
+    path: Option<PathBuf>,
+    /// Directory the file browser starts in, instead of the current directory
+    #[arg(long, value_name = "DIR")]
+    start_dir: Option<PathBuf>,
+}
+
+impl Args {
+    /// Checks the arguments and reads the file to open first, before the terminal is taken over
+    /// # Returns
+    /// The directory to browse, and the path and contents of the file to open first, if any
+    fn start(self) -> Result<(PathBuf, Option<OpenFile>), Box<dyn Error>> {
+        let mut start_dir = match self.start_dir {
+            Some(dir) => std::path::absolute(dir)?,
+            None => env::current_dir()?,
+        };
+        let first = match self.path {
+            None => None,
+            Some(path) if path == Path::new(STDIN) => {
+                let mut file_data = Vec::new();
+                io::stdin().read_to_end(&mut file_data)?;
+                Some((path, file_data))
+            }
+            Some(path) if path.is_dir() => {
+                start_dir = std::path::absolute(path)?;
+                None
+            }
+            Some(path) => {
+                let path = std::path::absolute(path)?;
+                let file_data =
+                    load_file_data(&path).map_err(|err| format!("{}: {}", path.display(), err))?;
+                Some((path, file_data))
+            }
+        };
+        if !start_dir.is_dir() {
+            return Err(format!("{} is not a directory", start_dir.display()).into());
+        }
+        Ok((start_dir, first))
+    }
+}
+
 struct AppState<'a> {
     selected_file: PathBuf,
     fxr: Option<ParsedFXR<'a>>,
     tree_state: TreeState,
+    /// Pane receiving the arrow keys
+    focus: Pane,
+    hex_view: HexView,
+    /// Leave out the sections that fail to parse instead of refusing the file
+    lenient: bool,
 }
 
 impl<'a> Default for AppState<'a> {
@@ -32,6 +99,9 @@ impl<'a> Default for AppState<'a> {
             // flattened: Vec::new(),
             fxr: None,
             tree_state: TreeState::default(),
+            focus: Pane::Tree,
+            hex_view: HexView::default(),
+            lenient: false,
         }
     }
 }
@@ -42,15 +112,24 @@ fn load_file_data(file_path: &PathBuf) -> Result<Vec<u8>, Box<dyn Error>> {
     Ok(file_data)
 }
 impl<'a> AppState<'a> {
-    fn new(selected_file: PathBuf, file_data: &'a [u8]) -> Result<Self, Box<dyn Error>> {
+    fn new(
+        selected_file: PathBuf,
+        file_data: &'a [u8],
+        lenient: bool,
+    ) -> Result<Self, Box<dyn Error>> {
         let mut ret = Self::default();
 
         // Parse the file
-        let fxr: ParsedFXR<'a> = parse_fxr(file_data)?;
+        let fxr: ParsedFXR<'a> = if lenient {
+            parse_fxr_lenient(file_data)?.0
+        } else {
+            parse_fxr(file_data)?
+        };
         ret.fxr = Some(fxr);
 
         Ok(Self {
             selected_file,
+            lenient,
             ..ret
         })
     }
@@ -70,22 +149,48 @@ fn setup() -> Result<(), Box<dyn std::error::Error>> {
     #[allow(unsafe_code)]
     let handler = CrashHandler::attach(unsafe {
         make_crash_event(move |context: &CrashContext| {
-            tracing::error!(
-                "Exception: {:x} at {:x}",
-                context.exception_code,
-                (*(*context.exception_pointers).ExceptionRecord).ExceptionAddress as usize
-            );
+            tracing::error!("{}", describe_crash(context));
 
             CrashEventResult::Handled(true)
         })
-    })
-    .unwrap();
+    })?;
     std::mem::forget(handler);
 
     Ok(())
 }
 
+/// One line describing a native crash from the platform's crash context
+#[cfg(target_os = "windows")]
+fn describe_crash(context: &CrashContext) -> String {
+    if context.exception_pointers.is_null() {
+        return format!("Exception: {:x}", context.exception_code);
+    }
+    #[allow(unsafe_code)]
+    let address =
+        unsafe { (*(*context.exception_pointers).ExceptionRecord).ExceptionAddress as usize };
+    format!("Exception: {:x} at {:x}", context.exception_code, address)
+}
+
+/// One line describing a native crash from the platform's crash context
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn describe_crash(context: &CrashContext) -> String {
+    format!(
+        "Signal: {} at {:x} (pid {}, tid {})",
+        context.siginfo.ssi_signo, context.siginfo.ssi_addr, context.pid, context.tid
+    )
+}
+
+/// One line describing a native crash from the platform's crash context
+#[cfg(target_os = "macos")]
+fn describe_crash(context: &CrashContext) -> String {
+    match &context.exception {
+        Some(exception) => format!("Exception: {:x} code {:x}", exception.kind, exception.code),
+        None => "Crash without an exception".to_string(),
+    }
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let (start_dir, first) = Args::parse().start()?;
     let subscriber = setup();
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -93,7 +198,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let result = terminal_main_wrapper::<CrosstermBackend<io::Stdout>>(&mut terminal);
+    let result = terminal_main_wrapper(&mut terminal, start_dir, first);
 
     disable_raw_mode()?;
     execute!(
@@ -103,7 +208,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     )?;
     terminal.show_cursor()?;
 
-    if let Err(_err) = result {
+    let Ok(result) = result else {
         let partial = "Application crashed due to a panic.".to_string();
         let message = match env::var("RUST_BACKTRACE").unwrap_or_default().as_str() {
             "1" => "Check fxr_binary_reader.log for more information..".to_string(),
@@ -111,38 +216,148 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         };
         let full_message = format!("{} {}", partial, message);
         return Err(Box::new(std::io::Error::other(full_message.to_string())));
-    }
+    };
     drop(subscriber);
-    Ok(())
+    result
 }
 
-fn terminal_main_wrapper<'a, B: Backend>(
+/// Runs the file browser, opening `first` before showing it
+fn terminal_main_wrapper<'a>(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
-) -> Result<Result<(), Box<dyn Error>>, Box<(dyn Any + Send + 'a)>> {
+    start_dir: PathBuf,
+    mut first: Option<OpenFile>,
+) -> Result<Result<(), Box<dyn Error>>, Box<dyn Any + Send + 'a>> {
     std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-        let current_dir = env::current_dir().unwrap();
-        let files = file_entries(&current_dir).unwrap();
-
-        let selected_file_index: usize = 0;
-        let selected_file = file_selection_loop(terminal, files, selected_file_index);
-        if selected_file.is_none() {
-            // User canceled the file selection
-            return Ok(());
-        }
-        let selected_file = selected_file.unwrap().unwrap();
-        // Load file data
-        let file_data = load_file_data(&selected_file).unwrap();
+        let mut files = file_entries(&start_dir)?;
+        let mut selected_file_index: usize = 0;
 
-        // Initialize AppState with file data
-        let state = AppState::new(selected_file, &file_data).unwrap();
+        loop {
+            // Come back to the same entry after closing the file or failing to open it
+            let (selected_file, file_data) = match first.take() {
+                Some((selected_file, file_data)) => {
+                    if selected_file != Path::new(STDIN) {
+                        (files, selected_file_index) = browser_position(&selected_file)?;
+                    }
+                    (selected_file, file_data)
+                }
+                None => {
+                    let selected_file =
+                        match file_selection_loop(terminal, files, selected_file_index) {
+                            // User canceled the file selection
+                            None => return Ok(()),
+                            Some(selected_file) => selected_file?,
+                        };
+                    (files, selected_file_index) = browser_position(&selected_file)?;
+                    match load_file_data(&selected_file) {
+                        Ok(file_data) => (selected_file, file_data),
+                        Err(err) => {
+                            tracing::error!("Couldn't read {}: {}", selected_file.display(), err);
+                            error_dialog(terminal, &selected_file, err.as_ref(), false)?;
+                            continue;
+                        }
+                    }
+                }
+            };
 
-        if terminal_draw_loop(terminal, state).is_some() {
-            Ok(())
-        } else {
-            terminal_main_wrapper::<B>(terminal).unwrap()
+            // Pick a file from an archive, or go back to the browser
+            let (selected_file, file_data) = if is_bnd4(&file_data) || is_dcx(&file_data) {
+                match archive_entry(terminal, &selected_file, &file_data) {
+                    Ok(Some(entry)) => entry,
+                    Ok(None) => continue,
+                    Err(err) => {
+                        tracing::error!("Couldn't unpack {}: {}", selected_file.display(), err);
+                        error_dialog(terminal, &selected_file, err.as_ref(), false)?;
+                        continue;
+                    }
+                }
+            } else {
+                (selected_file, file_data)
+            };
+
+            let mut lenient = false;
+            loop {
+                match open_file(terminal, &selected_file, &file_data, lenient) {
+                    Some(Ok(())) => return Ok(()),
+                    None => break,
+                    Some(Err(err)) => {
+                        tracing::error!("Couldn't open {}: {}", selected_file.display(), err);
+                        match error_dialog(terminal, &selected_file, err.as_ref(), !lenient)? {
+                            Recovery::Lenient => lenient = true,
+                            Recovery::Back => break,
+                        }
+                    }
+                }
+            }
         }
     }))
 }
+
+/// Parses a file, then browses it
+/// # Returns
+/// The result of `terminal_draw_loop`
+fn open_file(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    selected_file: &Path,
+    file_data: &[u8],
+    lenient: bool,
+) -> Option<Result<(), Box<dyn Error>>> {
+    // Initialize AppState with file data
+    match AppState::new(selected_file.to_path_buf(), file_data, lenient) {
+        Ok(state) => terminal_draw_loop(terminal, state, file_data.to_vec()),
+        Err(err) => Some(Err(err)),
+    }
+}
+
+/// Paths of a directory's entries and the lines describing them, from `file_entries`
+type FileEntries = (Vec<PathBuf>, Vec<String>);
+
+/// Lists the `.fxr` files of a BND4 archive, or of a DCX compressed one, and reads the one the
+/// user picks. An archive holding a single `.fxr` file opens it without asking.
+/// # Returns
+/// The picked file as `<archive>/<name>`, or `None` when the user goes back to the browser
+fn archive_entry(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    archive: &Path,
+    archive_data: &[u8],
+) -> Result<Option<OpenFile>, Box<dyn Error>> {
+    let mut files = archive::unpack(archive_data)?;
+    // A compressed lone file, such as `f000302421.fxr.dcx`, is named after the archive
+    let archive_name = archive.file_name().unwrap_or_default().to_string_lossy();
+    for file in files.iter_mut().filter(|file| file.name.is_empty()) {
+        file.name = archive_name.trim_end_matches(".dcx").to_string();
+    }
+    files.retain(|file| file.file_name().to_lowercase().ends_with(".fxr"));
+    let index = match files.len() {
+        0 => return Err(format!("{} holds no .fxr files", archive.display()).into()),
+        1 => 0,
+        _ => match archive_selection_loop(terminal, archive, &files)? {
+            Some(index) => index,
+            None => return Ok(None),
+        },
+    };
+    let file = files.swap_remove(index);
+    let path = match archive == Path::new(STDIN) {
+        true => archive.to_path_buf(),
+        false => archive.join(file.file_name()),
+    };
+    Ok(Some((path, file.data)))
+}
+
+/// The entries of the directory holding `file`, and the index of `file` among them. A file
+/// extracted from an archive is placed on the archive.
+fn browser_position(file: &Path) -> Result<(FileEntries, usize), Box<dyn Error>> {
+    let file = match file.parent() {
+        Some(archive) if archive.is_file() => archive,
+        _ => file,
+    };
+    let dir = match file.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => env::current_dir()?,
+    };
+    let files = file_entries(&dir)?;
+    let index = files.0.iter().position(|entry| entry == file).unwrap_or(0);
+    Ok((files, index))
+}
 fn file_entries(
     current_dir: &std::path::PathBuf,
 ) -> Result<(Vec<PathBuf>, Vec<String>), Box<dyn std::error::Error>> {
@@ -162,53 +377,59 @@ fn file_entries(
         }
     }
 
-    let mut files = Vec::new();
-    let file_data: Vec<String> = fs::read_dir(current_dir)?
-        .filter_map(|entry| entry.ok())
-        .filter(|entry| {
-            (entry.file_type().unwrap().is_dir() && !entry.file_name().to_string_lossy().starts_with("."))
-                || entry
-                    .file_name()
-                    .into_string()
-                    .map(|name| name.ends_with(".fxr"))
-                    .unwrap_or(false)
-        })
-        .map(|entry: fs::DirEntry| {
-            let entry_as_pathbuf = entry.path();
-            files.push(entry_as_pathbuf.clone()); // Store the PathBuf in the vector
-            let metadata = entry.metadata().unwrap();
-            let file_attributes = metadata.file_attributes();
-            let creation_time = metadata.creation_time();
-            let last_access_time = metadata.last_access_time();
-            let last_write_time = metadata.last_write_time();
-            let file_size = metadata.file_size();
-
-            format!(
-                "{:<30} | Attrs: {:<10} | Created: {:<20} | Accessed: {:<20} | Modified: {:<20} | Size: {:<10}",
-                entry.file_name().to_string_lossy(),
-                file_attributes,
-                DateTime::from_timestamp(
-                    ((creation_time - 116444736000000000) / 10_000_000) as i64,
-                    0
-                )
-                .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
-                .unwrap_or_else(|| "Invalid Date".to_string()),
-                DateTime::from_timestamp(
-                    ((last_access_time - 116444736000000000) / 10_000_000) as i64,
-                    0
-                )
-                .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
-                .unwrap_or_else(|| "Invalid Date".to_string()),
-                DateTime::from_timestamp(
-                    ((last_write_time - 116444736000000000) / 10_000_000) as i64,
-                    0
-                )
-                .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
-                .unwrap_or_else(|| "Invalid Date".to_string()),
-                format_file_size(file_size),
-            )
+    /// Timestamps some platforms or filesystems don't record (e.g. creation time on older Linux
+    /// filesystems) are shown as `-`
+    fn format_time(time: io::Result<SystemTime>) -> String {
+        time.map(|time| {
+            DateTime::<Utc>::from(time)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
         })
-        .collect();
+        .unwrap_or_else(|_| "-".to_string())
+    }
+
+    /// `dir`, `file` or `link`, followed by `ro` for read-only entries
+    fn file_attributes(metadata: &fs::Metadata) -> String {
+        let kind = if metadata.is_dir() {
+            "dir"
+        } else if metadata.is_symlink() {
+            "link"
+        } else {
+            "file"
+        };
+        if metadata.permissions().readonly() {
+            format!("{} ro", kind)
+        } else {
+            kind.to_string()
+        }
+    }
+
+    let mut files = Vec::new();
+    let mut file_data = Vec::new();
+    for entry in fs::read_dir(current_dir)?.filter_map(|entry| entry.ok()) {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let listed = if entry.file_type()?.is_dir() {
+            !name.starts_with(".")
+        } else {
+            // `.fxr` files and the BND4/DCX archives holding them, e.g. `sfxbnd_c2010.ffxbnd.dcx`
+            name.ends_with(".fxr") || name.ends_with(".dcx") || name.ends_with("bnd")
+        };
+        if !listed {
+            continue;
+        }
+        files.push(entry.path()); // Store the PathBuf in the vector
+        let metadata = entry.metadata()?;
+
+        file_data.push(format!(
+            "{:<30} | Attrs: {:<10} | Created: {:<20} | Accessed: {:<20} | Modified: {:<20} | Size: {:<10}",
+            name,
+            file_attributes(&metadata),
+            format_time(metadata.created()),
+            format_time(metadata.accessed()),
+            format_time(metadata.modified()),
+            format_file_size(metadata.len()),
+        ));
+    }
 
     Ok((files, file_data)) // Return both the Vec<PathBuf> and the Vec<String>
 }
@@ -0,0 +1,374 @@
+use flate2::read::ZlibDecoder;
+use std::io::Read;
+use thiserror::Error;
+
+/// One file stored in a BND4 archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveFile {
+    pub id: Option<i32>,
+    /// Path stored in the archive, often a full Windows path such as
+    /// `N:\GR\data\INTERROOT_win64\sfx\effect\f000302421.fxr`
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+impl ArchiveFile {
+    /// The last component of `name`, for listing and extracting the file
+    /// # Example
+    /// ```rust
+    /// use fxr_binary_reader::fxr::archive::ArchiveFile;
+    ///
+    /// let file = ArchiveFile {
+    ///     id: Some(0),
+    ///     name: r"N:\GR\data\sfx\effect\f000302421.fxr".into(),
+    ///     data: Vec::new(),
+    /// };
+    /// assert_eq!(file.file_name(), "f000302421.fxr");
+    /// ```
+    pub fn file_name(&self) -> &str {
+        self.name.rsplit(['\\', '/']).next().unwrap_or(&self.name)
+    }
+}
+
+/// Why an archive couldn't be read
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ArchiveError {
+    #[error("expected {expected:?} at 0x{offset:X}")]
+    Magic {
+        expected: &'static str,
+        offset: usize,
+    },
+    #[error("{what} at 0x{offset:X} is past the end of the archive")]
+    OutOfBounds { what: &'static str, offset: usize },
+    #[error("{0} compressed DCX isn't supported, decompress it with another tool first")]
+    UnsupportedCompression(String),
+    #[error("couldn't decompress the DCX data: {0}")]
+    Decompress(String),
+    #[error("decompressed {found} bytes, the DCX header says {expected}")]
+    SizeMismatch { expected: usize, found: usize },
+}
+
+/// Whether `bytes` start like a DCX compressed file
+pub fn is_dcx(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"DCX\0")
+}
+
+/// Whether `bytes` start like a BND4 archive
+pub fn is_bnd4(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"BND4")
+}
+
+/// Reads integers at absolute offsets, failing instead of panicking past the end
+struct Reader<'a> {
+    bytes: &'a [u8],
+    big_endian: bool,
+}
+
+impl<'a> Reader<'a> {
+    fn slice(
+        &self,
+        what: &'static str,
+        offset: usize,
+        len: usize,
+    ) -> Result<&'a [u8], ArchiveError> {
+        offset
+            .checked_add(len)
+            .and_then(|end| self.bytes.get(offset..end))
+            .ok_or(ArchiveError::OutOfBounds { what, offset })
+    }
+
+    fn array<const N: usize>(
+        &self,
+        what: &'static str,
+        offset: usize,
+    ) -> Result<[u8; N], ArchiveError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.slice(what, offset, N)?);
+        if self.big_endian {
+            array.reverse();
+        }
+        Ok(array)
+    }
+
+    fn u8(&self, what: &'static str, offset: usize) -> Result<u8, ArchiveError> {
+        Ok(self.slice(what, offset, 1)?[0])
+    }
+
+    fn u32(&self, what: &'static str, offset: usize) -> Result<u32, ArchiveError> {
+        self.array(what, offset).map(u32::from_le_bytes)
+    }
+
+    fn i32(&self, what: &'static str, offset: usize) -> Result<i32, ArchiveError> {
+        self.array(what, offset).map(i32::from_le_bytes)
+    }
+
+    fn u64(&self, what: &'static str, offset: usize) -> Result<u64, ArchiveError> {
+        self.array(what, offset).map(u64::from_le_bytes)
+    }
+
+    fn usize(&self, what: &'static str, offset: usize, long: bool) -> Result<usize, ArchiveError> {
+        let value = match long {
+            true => self.u64(what, offset)?,
+            false => self.u32(what, offset)?.into(),
+        };
+        usize::try_from(value).map_err(|_| ArchiveError::OutOfBounds { what, offset })
+    }
+
+    fn magic(&self, expected: &'static str, offset: usize) -> Result<(), ArchiveError> {
+        match self.bytes.get(offset..offset + expected.len()) {
+            Some(found) if found == expected.as_bytes() => Ok(()),
+            _ => Err(ArchiveError::Magic { expected, offset }),
+        }
+    }
+}
+
+/// Decompresses a DCX file. Only DFLT (zlib) compression is supported; KRAK (Oodle Kraken) and
+/// the other formats are reported as [`ArchiveError::UnsupportedCompression`].
+/// # Arguments
+/// * `bytes` - The whole DCX file
+/// # Returns
+/// The decompressed file, usually a BND4 archive
+/// # Example
+/// ```rust
+/// use fxr_binary_reader::fxr::archive::{ArchiveError, decompress_dcx};
+/// use std::io::Write;
+///
+/// let data = b"FXR\0 compressed";
+/// let mut zlib = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::best());
+/// zlib.write_all(data).unwrap();
+/// let zlib = zlib.finish().unwrap();
+///
+/// let be = |value: u32| value.to_be_bytes();
+/// let mut dcx = b"DCX\0".to_vec();
+/// for value in [0x11000, 0x18, 0x24, 0x44, 0x4C] {
+///     dcx.extend(be(value));
+/// }
+/// dcx.extend(b"DCS\0");
+/// dcx.extend(be(data.len() as u32));
+/// dcx.extend(be(zlib.len() as u32));
+/// dcx.extend(b"DCP\0DFLT");
+/// dcx.extend(be(0x20));
+/// dcx.extend([9, 0, 0, 0]);
+/// dcx.extend([0; 12]);
+/// dcx.extend(be(0x10100));
+/// dcx.extend(b"DCA\0");
+/// dcx.extend(be(8));
+/// dcx.extend(&zlib);
+/// assert_eq!(decompress_dcx(&dcx).unwrap(), data);
+///
+/// // A corrupt header claiming 4 GiB is an error, not an allocation
+/// let mut corrupt = dcx.clone();
+/// corrupt[0x1C..0x20].copy_from_slice(&be(0xFFFFFFFF));
+/// assert_eq!(
+///     decompress_dcx(&corrupt),
+///     Err(ArchiveError::SizeMismatch { expected: 0xFFFFFFFF, found: data.len() })
+/// );
+///
+/// dcx[0x28..0x2C].copy_from_slice(b"KRAK");
+/// assert_eq!(
+///     decompress_dcx(&dcx),
+///     Err(ArchiveError::UnsupportedCompression("KRAK".into()))
+/// );
+/// ```
+pub fn decompress_dcx(bytes: &[u8]) -> Result<Vec<u8>, ArchiveError> {
+    let reader = Reader {
+        bytes,
+        big_endian: true,
+    };
+    reader.magic("DCX\0", 0)?;
+    let dcs = reader.usize("DCS offset", 0x8, false)?;
+    let dcp = reader.usize("DCP offset", 0xC, false)?;
+    let dca = reader.usize("DCA offset", 0x10, false)?;
+    let data = reader.usize("data offset", 0x14, false)?;
+    reader.magic("DCS\0", dcs)?;
+    let uncompressed = reader.usize("uncompressed size", dcs + 4, false)?;
+    let compressed = reader.usize("compressed size", dcs + 8, false)?;
+    reader.magic("DCP\0", dcp)?;
+    let format = reader.slice("compression format", dcp + 4, 4)?;
+    if format != b"DFLT" {
+        return Err(ArchiveError::UnsupportedCompression(
+            String::from_utf8_lossy(format).into_owned(),
+        ));
+    }
+    reader.magic("DCA\0", dca)?;
+
+    // The header's sizes aren't trusted: zlib rarely compresses these files more than 16 times,
+    // and reading stops one byte past the stated size, which is enough to report a mismatch
+    let mut decompressed = Vec::with_capacity(uncompressed.min(compressed.saturating_mul(16)));
+    ZlibDecoder::new(reader.slice("compressed data", data, compressed)?)
+        .take(uncompressed as u64 + 1)
+        .read_to_end(&mut decompressed)
+        .map_err(|err| ArchiveError::Decompress(err.to_string()))?;
+    if decompressed.len() != uncompressed {
+        return Err(ArchiveError::SizeMismatch {
+            expected: uncompressed,
+            found: decompressed.len(),
+        });
+    }
+    Ok(decompressed)
+}
+
+/// Bits of the BND4 format byte, after normalizing its bit order
+const FORMAT_IDS: u8 = 0b0000_0010;
+const FORMAT_NAMES1: u8 = 0b0000_0100;
+const FORMAT_NAMES2: u8 = 0b0000_1000;
+const FORMAT_LONG_OFFSETS: u8 = 0b0001_0000;
+const FORMAT_COMPRESSION: u8 = 0b0010_0000;
+/// Bit of a file's flags byte marking its data as DCX compressed
+const FILE_COMPRESSED: u8 = 0b0000_0001;
+
+/// Reads every file of a BND4 archive, decompressing the ones stored as DCX
+/// # Arguments
+/// * `bytes` - The whole archive, already decompressed if it was a DCX file
+/// # Returns
+/// The files in the order the archive lists them
+/// # Example
+/// ```rust
+/// use fxr_binary_reader::fxr::archive::read_bnd4;
+///
+/// // Header, one 0x24-byte file header, the name, then the data
+/// let mut bnd = b"BND4".to_vec();
+/// bnd.extend([0, 0, 0, 0, 0, 0, 1, 0]);
+/// bnd.extend(1u32.to_le_bytes()); // file count
+/// bnd.extend(0x40u64.to_le_bytes());
+/// bnd.extend(b"07D7R6\0\0");
+/// bnd.extend(0x24u64.to_le_bytes()); // file header size
+/// bnd.extend(0u64.to_le_bytes());
+/// bnd.extend([0, 0x74, 0, 0]); // Shift-JIS names; IDs, names and uncompressed sizes
+/// bnd.extend([0; 12]);
+/// bnd.extend([0x40, 0, 0, 0]);
+/// bnd.extend((-1i32).to_le_bytes());
+/// bnd.extend(4u64.to_le_bytes()); // compressed size
+/// bnd.extend(4u64.to_le_bytes()); // uncompressed size
+/// bnd.extend(0x70u32.to_le_bytes()); // data offset
+/// bnd.extend(7i32.to_le_bytes()); // id
+/// bnd.extend(0x64u32.to_le_bytes()); // name offset
+/// bnd.extend(b"a.fxr\0\0\0\0\0\0\0");
+/// bnd.extend(b"FXR\0");
+///
+/// let files = read_bnd4(&bnd).unwrap();
+/// assert_eq!(files.len(), 1);
+/// assert_eq!(files[0].id, Some(7));
+/// assert_eq!(files[0].name, "a.fxr");
+/// assert_eq!(files[0].data, b"FXR\0");
+/// assert!(read_bnd4(&bnd[..0x60]).is_err());
+///
+/// // A file header size that overflows is an error rather than a panic
+/// bnd[0x20..0x28].copy_from_slice(&u64::MAX.to_le_bytes());
+/// assert_eq!(
+///     read_bnd4(&bnd).unwrap_err().to_string(),
+///     "file headers at 0x40 is past the end of the archive"
+/// );
+/// ```
+pub fn read_bnd4(bytes: &[u8]) -> Result<Vec<ArchiveFile>, ArchiveError> {
+    let mut reader = Reader {
+        bytes,
+        big_endian: false,
+    };
+    reader.magic("BND4", 0)?;
+    reader.big_endian = reader.u8("endianness", 0x9)? != 0;
+    let bit_big_endian = reader.u8("bit order", 0xA)? == 0;
+    let count = reader.u32("file count", 0xC)?;
+    let header_size = reader.usize("file header size", 0x20, true)?;
+    let unicode = reader.u8("name encoding", 0x30)? != 0;
+    let raw_format = reader.u8("format", 0x31)?;
+    let as_is = bit_big_endian || (raw_format & 1 != 0 && raw_format & 0x80 == 0);
+    let format = match as_is {
+        true => raw_format,
+        false => raw_format.reverse_bits(),
+    };
+
+    // Check the whole header table first, so the offsets within it can't overflow
+    let table_size =
+        (count as usize)
+            .checked_mul(header_size)
+            .ok_or(ArchiveError::OutOfBounds {
+                what: "file headers",
+                offset: 0x40,
+            })?;
+    reader.slice("file headers", 0x40, table_size)?;
+
+    (0..count as usize)
+        .map(|index| {
+            let mut offset = 0x40 + index * header_size;
+            let raw_flags = reader.u8("file flags", offset)?;
+            let flags = match bit_big_endian {
+                true => raw_flags,
+                false => raw_flags.reverse_bits(),
+            };
+            let size = reader.usize("file size", offset + 0x8, true)?;
+            offset += 0x10;
+            if format & FORMAT_COMPRESSION != 0 {
+                offset += 8;
+            }
+            let long_offsets = format & FORMAT_LONG_OFFSETS != 0;
+            let data_offset = reader.usize("file data offset", offset, long_offsets)?;
+            offset += if long_offsets { 8 } else { 4 };
+            let mut id = None;
+            if format & FORMAT_IDS != 0 {
+                id = Some(reader.i32("file id", offset)?);
+                offset += 4;
+            }
+            let mut name = String::new();
+            if format & (FORMAT_NAMES1 | FORMAT_NAMES2) != 0 {
+                let name_offset = reader.usize("file name offset", offset, false)?;
+                name = read_name(&reader, name_offset, unicode)?;
+            }
+
+            let mut data = reader.slice("file data", data_offset, size)?.to_vec();
+            if flags & FILE_COMPRESSED != 0 {
+                data = decompress_dcx(&data)?;
+            }
+            Ok(ArchiveFile { id, name, data })
+        })
+        .collect()
+}
+
+/// Reads a null-terminated file name, UTF-16 or Shift-JIS
+fn read_name(reader: &Reader, offset: usize, unicode: bool) -> Result<String, ArchiveError> {
+    let rest = reader
+        .bytes
+        .get(offset..)
+        .ok_or(ArchiveError::OutOfBounds {
+            what: "file name",
+            offset,
+        })?;
+    if unicode {
+        let units: Vec<u16> = rest
+            .chunks_exact(2)
+            .map(|unit| match reader.big_endian {
+                true => u16::from_be_bytes([unit[0], unit[1]]),
+                false => u16::from_le_bytes([unit[0], unit[1]]),
+            })
+            .take_while(|&unit| unit != 0)
+            .collect();
+        Ok(String::from_utf16_lossy(&units))
+    } else {
+        let end = rest
+            .iter()
+            .position(|&byte| byte == 0)
+            .unwrap_or(rest.len());
+        let (name, _, _) = encoding_rs::SHIFT_JIS.decode(&rest[..end]);
+        Ok(name.into_owned())
+    }
+}
+
+/// Reads the files of an archive, decompressing it first if it is a DCX file. A DCX file that
+/// doesn't hold a BND4 archive, such as `f000302421.fxr.dcx`, is returned as a single unnamed
+/// file.
+/// # Arguments
+/// * `bytes` - A BND4 archive, or a DCX compressed file
+pub fn unpack(bytes: &[u8]) -> Result<Vec<ArchiveFile>, ArchiveError> {
+    if !is_dcx(bytes) {
+        return read_bnd4(bytes);
+    }
+    let data = decompress_dcx(bytes)?;
+    match is_bnd4(&data) {
+        true => read_bnd4(&data),
+        false => Ok(vec![ArchiveFile {
+            id: None,
+            name: String::new(),
+            data,
+        }]),
+    }
+}
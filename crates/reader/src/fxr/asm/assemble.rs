@@ -0,0 +1,513 @@
+use super::value_names;
+use crate::fxr::{
+    layout::{FieldSpan, struct_for},
+    walk::PathSegment,
+    writer::FxrWriter,
+};
+use std::collections::HashSet;
+use thiserror::Error;
+
+/// Largest image the assembler builds. Real files are well under a megabyte, so an offset or size
+/// past this is a typo such as `@0xFFFFFFFF` rather than a layout.
+pub const MAX_FILE_SIZE: usize = 0x400_0000;
+
+/// A syntax or value error in `fxr-asm` text, with a 1-based line and column
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{line}:{column}: {message}")]
+pub struct AsmError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+/// Assembles `fxr-asm` text, as printed by [`disassemble`], into a file image.
+///
+/// Each block is written at its `@offset`; fields left out of a block are zero, and bytes not
+/// covered by any block are zero up to the `size` directive. Block and field names are checked
+/// against the section structs, and values must fit the field they are assigned to: floats only
+/// go into 4-byte fields and strings must match the field size exactly. Values are written as
+/// numbers, strings or, for enum-like fields such as `Header.version` and the `Section6Entry`
+/// action type `unk00`, names such as `Sekiro` and `BillboardEx`. Offsets and sizes must stay within
+/// [`MAX_FILE_SIZE`].
+///
+/// # Arguments
+/// - `text`: The `fxr-asm` source.
+///
+/// # Returns
+/// - `Ok(FxrWriter)`: The assembled image, ready for further edits or [`FxrWriter::into_bytes`].
+/// - `Err(AsmError)`: The first error, with its line and column.
+///
+/// # Example
+/// ```rust
+/// use fxr_binary_reader::fxr::asm::assemble::assemble;
+///
+/// let text = "Section11 @0x4 {\n    1.0f\n    -1\n}\nsize 0x10\n";
+/// let bytes = assemble(text).unwrap().into_bytes();
+/// assert_eq!(bytes.len(), 0x10);
+/// assert_eq!(&bytes[4..12], &[0x00, 0x00, 0x80, 0x3F, 0xFF, 0xFF, 0xFF, 0xFF]);
+///
+/// let error = assemble("Section6[0] @0x0 {\n    unk99 = 1\n}").unwrap_err();
+/// assert_eq!(error.to_string(), "2:5: Section6Entry has no field `unk99`");
+///
+/// let bytes = assemble("Header @0x0 {\n    version = Sekiro\n}").unwrap().into_bytes();
+/// assert_eq!(&bytes[6..8], &5u16.to_le_bytes());
+/// let bytes = assemble("Section6[0] @0x0 {\n    unk00 = BillboardEx\n}").unwrap().into_bytes();
+/// assert_eq!(&bytes[0..2], &603u16.to_le_bytes());
+///
+/// let error = assemble("Header @0x0 {\n    version = Nioh\n}").unwrap_err();
+/// assert_eq!(
+///     error.to_string(),
+///     "2:15: `version` has no value named `Nioh`, expected DarkSouls3 or Sekiro"
+/// );
+///
+/// let error = assemble("Header @0x0 {\n    ffx_id = Sekiro\n}").unwrap_err();
+/// assert_eq!(error.to_string(), "2:14: `ffx_id` has no named values, write `Sekiro` as a number");
+///
+/// let error = assemble("raw @0xFFFFFFFF { 00 }").unwrap_err();
+/// assert_eq!(error.to_string(), "1:6: 0xFFFFFFFF is past the largest supported file (0x4000000 bytes)");
+/// ```
+///
+/// [`disassemble`]: super::disassemble::disassemble
+pub fn assemble(text: &str) -> Result<FxrWriter, AsmError> {
+    let mut parser = Parser {
+        tokens: tokenize(text)?,
+        position: 0,
+        writer: FxrWriter::new(),
+    };
+    parser.document()?;
+    Ok(parser.writer)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    /// Numeric literal, classified when it is used so `raw` blocks can read `3F` as hex
+    Number(String),
+    Str(Vec<u8>),
+    At,
+    Equals,
+    LeftBrace,
+    RightBrace,
+    LeftBracket,
+    RightBracket,
+    Eof,
+}
+
+#[derive(Debug, Clone)]
+struct Spanned {
+    token: Token,
+    line: usize,
+    column: usize,
+}
+
+impl Spanned {
+    fn error(&self, message: impl Into<String>) -> AsmError {
+        AsmError {
+            line: self.line,
+            column: self.column,
+            message: message.into(),
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Spanned>, AsmError> {
+    let mut tokens = Vec::new();
+    for (line_index, line) in text.lines().enumerate() {
+        let chars: Vec<char> = line.chars().collect();
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            let start = i;
+            let error = |message: String| AsmError {
+                line: line_index + 1,
+                column: start + 1,
+                message,
+            };
+            let token = match c {
+                '#' => break,
+                c if c.is_whitespace() => {
+                    i += 1;
+                    continue;
+                }
+                '@' => Token::At,
+                '=' => Token::Equals,
+                '{' => Token::LeftBrace,
+                '}' => Token::RightBrace,
+                '[' => Token::LeftBracket,
+                ']' => Token::RightBracket,
+                '"' => {
+                    let (bytes, end) = string(&chars, i).map_err(error)?;
+                    i = end;
+                    Token::Str(bytes)
+                }
+                c if c.is_ascii_digit() || c == '-' => {
+                    let end = number_end(&chars, i);
+                    let word: String = chars[i..end].iter().collect();
+                    i = end;
+                    Token::Number(word)
+                }
+                c if c.is_ascii_alphabetic() || c == '_' => {
+                    let end = (i..chars.len())
+                        .find(|&j| !(chars[j].is_ascii_alphanumeric() || chars[j] == '_'))
+                        .unwrap_or(chars.len());
+                    let word: String = chars[i..end].iter().collect();
+                    i = end;
+                    Token::Ident(word)
+                }
+                c => return Err(error(format!("unexpected character `{}`", c))),
+            };
+            if i == start {
+                i += 1;
+            }
+            tokens.push(Spanned {
+                token,
+                line: line_index + 1,
+                column: start + 1,
+            });
+        }
+    }
+    tokens.push(Spanned {
+        token: Token::Eof,
+        line: text.lines().count().max(1),
+        column: text.lines().last().map_or(0, |line| line.chars().count()) + 1,
+    });
+    Ok(tokens)
+}
+
+/// End of a numeric literal, allowing a sign after a decimal exponent as in `1e-5f`
+fn number_end(chars: &[char], start: usize) -> usize {
+    let is_hex = chars[start..].starts_with(&['0', 'x']) || chars[start..].starts_with(&['0', 'X']);
+    let mut end = start + 1;
+    while end < chars.len() {
+        let c = chars[end];
+        let exponent_sign =
+            (c == '-' || c == '+') && !is_hex && matches!(chars[end - 1], 'e' | 'E');
+        if !(c.is_ascii_alphanumeric() || c == '.' || c == '_' || exponent_sign) {
+            break;
+        }
+        end += 1;
+    }
+    end
+}
+
+/// A classified numeric literal
+enum Number {
+    Int(i64),
+    Float(f32),
+}
+
+fn number(word: &str) -> Result<Number, String> {
+    let invalid = || format!("invalid number `{}`", word);
+    let (negative, digits) = match word.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, word),
+    };
+    let digits = digits.replace('_', "");
+    if let Some(hex) = digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        let value = i64::from_str_radix(hex, 16).map_err(|_| invalid())?;
+        return Ok(Number::Int(if negative { -value } else { value }));
+    }
+    if let Some(float) = digits.strip_suffix('f') {
+        let value: f32 = float.parse().map_err(|_| invalid())?;
+        return Ok(Number::Float(if negative { -value } else { value }));
+    }
+    let value: i64 = digits.parse().map_err(|_| invalid())?;
+    Ok(Number::Int(if negative { -value } else { value }))
+}
+
+/// Parses a string literal starting at the opening quote, returning its bytes and the index
+/// after the closing quote
+fn string(chars: &[char], start: usize) -> Result<(Vec<u8>, usize), String> {
+    let mut bytes = Vec::new();
+    let mut i = start + 1;
+    while i < chars.len() {
+        match chars[i] {
+            '"' => return Ok((bytes, i + 1)),
+            '\\' => {
+                let escape = chars.get(i + 1).copied();
+                match escape {
+                    Some('0') => bytes.push(0),
+                    Some('n') => bytes.push(b'\n'),
+                    Some('\\') => bytes.push(b'\\'),
+                    Some('"') => bytes.push(b'"'),
+                    Some('x') => {
+                        let hex: String = chars.iter().skip(i + 2).take(2).collect();
+                        let byte = u8::from_str_radix(&hex, 16)
+                            .map_err(|_| format!("invalid escape `\\x{}`", hex))?;
+                        bytes.push(byte);
+                        i += 2;
+                    }
+                    other => {
+                        return Err(format!(
+                            "unknown escape `\\{}`",
+                            other.map(String::from).unwrap_or_default()
+                        ));
+                    }
+                }
+                i += 2;
+            }
+            c if c.is_ascii() => {
+                bytes.push(c as u8);
+                i += 1;
+            }
+            c => return Err(format!("non-ASCII character `{}` in string", c)),
+        }
+    }
+    Err("unterminated string".to_string())
+}
+
+struct Parser {
+    tokens: Vec<Spanned>,
+    position: usize,
+    writer: FxrWriter,
+}
+
+impl Parser {
+    fn peek(&self) -> &Spanned {
+        &self.tokens[self.position.min(self.tokens.len() - 1)]
+    }
+
+    fn next(&mut self) -> Spanned {
+        let token = self.peek().clone();
+        self.position += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token, description: &str) -> Result<Spanned, AsmError> {
+        let token = self.next();
+        if token.token == expected {
+            Ok(token)
+        } else {
+            Err(token.error(format!("expected {}", description)))
+        }
+    }
+
+    /// Reads a non-negative integer such as an offset or an index
+    fn unsigned(&mut self, description: &str) -> Result<usize, AsmError> {
+        let token = self.next();
+        match &token.token {
+            Token::Number(word) => match number(word) {
+                Ok(Number::Int(value)) if value >= 0 => Ok(value as usize),
+                Ok(_) => Err(token.error(format!("expected {}", description))),
+                Err(message) => Err(token.error(message)),
+            },
+            _ => Err(token.error(format!("expected {}", description))),
+        }
+    }
+
+    /// Reads an offset or size, checked against [`MAX_FILE_SIZE`] right away so a typo is
+    /// reported at the number rather than after the block it starts
+    /// # Returns
+    /// The value and its token, for reporting where the block ends past the limit
+    fn offset(&mut self, description: &str) -> Result<(usize, Spanned), AsmError> {
+        let token = self.peek().clone();
+        let value = self.unsigned(description)?;
+        within_limit(&token, value, 0)?;
+        Ok((value, token))
+    }
+
+    fn document(&mut self) -> Result<(), AsmError> {
+        loop {
+            let token = self.peek().clone();
+            match &token.token {
+                Token::Eof => return Ok(()),
+                Token::Ident(name) if name == "size" => {
+                    self.next();
+                    let (size, token) = self.offset("a file size")?;
+                    within_limit(&token, size, 0)?;
+                    self.writer.pad_to(size);
+                }
+                Token::Ident(name) if name == "raw" => {
+                    self.next();
+                    self.raw()?;
+                }
+                Token::Ident(_) => self.block()?,
+                _ => return Err(token.error("expected a block, `raw` or `size`")),
+            }
+        }
+    }
+
+    /// `raw @offset { 01 02 ... }`
+    fn raw(&mut self) -> Result<(), AsmError> {
+        self.expect(Token::At, "`@`")?;
+        let (offset, offset_token) = self.offset("an offset")?;
+        self.expect(Token::LeftBrace, "`{`")?;
+        let mut bytes = Vec::new();
+        loop {
+            let token = self.next();
+            match token.token {
+                Token::RightBrace => break,
+                // Hex pairs such as `3F` lex as numbers, `AB` as identifiers
+                Token::Ident(ref word) | Token::Number(ref word) => bytes.push(
+                    u8::from_str_radix(word, 16)
+                        .map_err(|_| token.error(format!("invalid byte `{}`", word)))?,
+                ),
+                _ => return Err(token.error("expected a hex byte or `}`")),
+            }
+        }
+        within_limit(&offset_token, offset, bytes.len())?;
+        self.writer.write_bytes(offset, &bytes);
+        Ok(())
+    }
+
+    /// `Section6[0] @offset { field = value ... child blocks ... }`
+    fn block(&mut self) -> Result<(), AsmError> {
+        let name = self.next();
+        let segment = self.segment(&name)?;
+        self.expect(Token::At, "`@`")?;
+        let (offset, offset_token) = self.offset("an offset")?;
+        self.expect(Token::LeftBrace, "`{`")?;
+
+        if segment.section == 11 && segment.index.is_none() {
+            return self.section11_values(offset, &offset_token);
+        }
+        let Some((kind, fields)) = struct_for(segment) else {
+            return Err(name.error(format!("unknown block `{}`", segment)));
+        };
+
+        let size = fields.last().map_or(0, |span| span.offset + span.size);
+        let mut bytes = vec![0u8; size];
+        let mut assigned = HashSet::new();
+        loop {
+            let token = self.next();
+            let Token::Ident(ident) = &token.token else {
+                if token.token == Token::RightBrace {
+                    break;
+                }
+                return Err(token.error("expected a field, a nested block or `}`"));
+            };
+            if self.peek().token != Token::Equals {
+                self.position -= 1;
+                self.block()?;
+                continue;
+            }
+            self.next();
+            let Some(span) = fields.iter().find(|span| span.name == ident) else {
+                return Err(token.error(format!("{} has no field `{}`", kind, ident)));
+            };
+            if !assigned.insert(span.name) {
+                return Err(token.error(format!("`{}` is assigned twice", ident)));
+            }
+            let value = self.next();
+            bytes[span.range()].copy_from_slice(&encode(&value, kind, span)?);
+        }
+        within_limit(&offset_token, offset, bytes.len())?;
+        self.writer.write_bytes(offset, &bytes);
+        Ok(())
+    }
+
+    /// Parses `Header`, `Section4` or `Section6[0]`
+    fn segment(&mut self, name: &Spanned) -> Result<PathSegment, AsmError> {
+        let Token::Ident(word) = &name.token else {
+            return Err(name.error("expected a block name"));
+        };
+        let section = match word.as_str() {
+            "Header" => Some(0),
+            _ => word
+                .strip_prefix("Section")
+                .and_then(|n| n.parse::<u8>().ok())
+                .filter(|n| (1..=14).contains(n)),
+        };
+        let Some(section) = section else {
+            return Err(name.error(format!("unknown block `{}`", word)));
+        };
+
+        let mut index = None;
+        if self.peek().token == Token::LeftBracket {
+            self.next();
+            index = Some(self.unsigned("an index")?);
+            self.expect(Token::RightBracket, "`]`")?;
+        }
+        Ok(PathSegment { section, index })
+    }
+
+    /// The body of a `Section11 @offset { ... }` block: one 4-byte value per slot
+    fn section11_values(&mut self, offset: usize, offset_token: &Spanned) -> Result<(), AsmError> {
+        let slot = FieldSpan {
+            name: "data",
+            offset: 0,
+            size: 4,
+        };
+        let mut bytes = Vec::new();
+        loop {
+            let token = self.next();
+            if token.token == Token::RightBrace {
+                break;
+            }
+            bytes.extend(encode(&token, "Section11Entry", &slot)?);
+        }
+        within_limit(offset_token, offset, bytes.len())?;
+        self.writer.write_bytes(offset, &bytes);
+        Ok(())
+    }
+}
+
+/// Checks that `len` bytes written at `offset` end within [`MAX_FILE_SIZE`]
+fn within_limit(token: &Spanned, offset: usize, len: usize) -> Result<(), AsmError> {
+    match offset.checked_add(len) {
+        Some(end) if end <= MAX_FILE_SIZE => Ok(()),
+        _ => Err(token.error(format!(
+            "0x{:X} is past the largest supported file (0x{:X} bytes)",
+            offset, MAX_FILE_SIZE
+        ))),
+    }
+}
+
+/// Encodes a literal as `span.size` little-endian bytes, reading names with the value names of
+/// `kind.field`
+fn encode(value: &Spanned, kind: &str, span: &FieldSpan) -> Result<Vec<u8>, AsmError> {
+    let bits = span.size * 8;
+    let names = value_names(kind, span.name);
+    let number = match &value.token {
+        Token::Number(word) => Some(number(word).map_err(|message| value.error(message))?),
+        Token::Ident(name) => names
+            .iter()
+            .find(|(_, n)| n == name)
+            .map(|(int, _)| Number::Int(*int as i64)),
+        _ => None,
+    };
+    match (&value.token, number) {
+        (_, Some(Number::Int(int))) => {
+            let min = -(1i64 << (bits - 1));
+            let max = (1i64 << bits) - 1;
+            if !(min..=max).contains(&int) {
+                return Err(value.error(format!(
+                    "{} doesn't fit in the {}-byte field `{}`",
+                    int, span.size, span.name
+                )));
+            }
+            Ok(int.to_le_bytes()[..span.size].to_vec())
+        }
+        (_, Some(Number::Float(float))) if span.size == 4 => {
+            Ok(float.to_bits().to_le_bytes().to_vec())
+        }
+        (_, Some(Number::Float(_))) => Err(value.error(format!(
+            "`{}` is a {}-byte field, floats need 4 bytes",
+            span.name, span.size
+        ))),
+        (Token::Ident(name), _) if names.is_empty() => Err(value.error(format!(
+            "`{}` has no named values, write `{}` as a number",
+            span.name, name
+        ))),
+        (Token::Ident(name), _) => {
+            let expected: Vec<&str> = names.iter().map(|(_, name)| *name).collect();
+            Err(value.error(format!(
+                "`{}` has no value named `{}`, expected {}",
+                span.name,
+                name,
+                expected.join(" or ")
+            )))
+        }
+        (Token::Str(bytes), _) if bytes.len() == span.size => Ok(bytes.clone()),
+        (Token::Str(bytes), _) => Err(value.error(format!(
+            "`{}` is a {}-byte field, the string has {} bytes",
+            span.name,
+            span.size,
+            bytes.len()
+        ))),
+        _ => Err(value.error("expected a value")),
+    }
+}
@@ -0,0 +1,225 @@
+use super::{role, value_names};
+use crate::fxr::{
+    Section11Entry,
+    fxr_parser_with_sections::parse_fxr,
+    layout::FieldSpan,
+    walk::{Node, is_offset_field, walk},
+};
+use std::{error::Error, fmt::Write};
+
+/// Prints a file as `fxr-asm`, an indented text form that [`assemble`] turns back into bytes.
+///
+/// Every struct becomes a block named after the last segment of its section path and placed at
+/// an explicit offset, with children nested inside their parent. Runs of Section11 slots are
+/// grouped into a single `Section11` block of values. Bytes that don't belong to any struct are
+/// kept in `raw` blocks, and `size` restores the file length:
+///
+/// ```text
+/// # node tree
+/// Section4 @0x110 {
+///     section6_count = 1
+///     section6_offset = 0x140
+///     # action
+///     Section6[0] @0x140 {
+///         unk00 = BillboardEx
+///         Section11 @0x200 {
+///             1.0f  # [0] 0x3F800000
+///             -1  # [1] 0xFFFFFFFF
+///         }
+///     }
+/// }
+/// raw @0x3F0 {
+///     01 02 03
+/// }
+/// size 0x400
+/// ```
+///
+/// Values are integers (`603`, `-1`, `0x140`), floats with an `f` suffix (`1.0f`), byte strings
+/// (`"FXR\0"`) or the names of enum-like values (`Sekiro`, `BillboardEx`), and `#` starts a
+/// comment. Because offsets are spelled out, assembling the output
+/// gives back the exact same bytes.
+///
+/// # Arguments
+/// - `data`: The raw bytes of an FXR file.
+///
+/// # Returns
+/// - `Ok(String)`: The `fxr-asm` text.
+/// - `Err(Box<dyn Error>)`: If the file fails to parse.
+///
+/// # Example
+/// ```rust
+/// use fxr_binary_reader::fxr::asm::{assemble::assemble, disassemble::disassemble};
+///
+/// let mut data = vec![0u8; 0xA0];
+/// data[0..4].copy_from_slice(b"FXR\0");
+/// data[6..8].copy_from_slice(&5u16.to_le_bytes()); // version
+/// data[8..12].copy_from_slice(&1u32.to_le_bytes()); // unk08
+/// data[0x10..0x14].copy_from_slice(&0x90u32.to_le_bytes()); // section1_offset
+/// data[0x14..0x18].copy_from_slice(&1u32.to_le_bytes()); // section1_count
+/// data[0x68..0x6C].copy_from_slice(&1u32.to_le_bytes()); // unk68
+///
+/// let text = disassemble(&data).unwrap();
+/// assert!(text.contains("Header @0x0 {\n    magic = \"FXR\\0\"\n"));
+/// assert!(text.contains("    version = Sekiro\n"));
+/// assert!(text.contains("    section1_offset = 0x90\n"));
+/// assert_eq!(assemble(&text).unwrap().into_bytes(), data);
+/// ```
+///
+/// [`assemble`]: super::assemble::assemble
+pub fn disassemble(data: &[u8]) -> Result<String, Box<dyn Error>> {
+    let fxr = parse_fxr(data)?;
+    let nodes = walk(&fxr);
+    let mut out = String::new();
+    write_document(&mut out, data, &nodes)?;
+    Ok(out)
+}
+
+fn write_document(out: &mut impl Write, data: &[u8], nodes: &[Node]) -> std::fmt::Result {
+    writeln!(out, "# fxr-asm v1")?;
+    let mut open = 0;
+    let mut i = 0;
+    while i < nodes.len() {
+        let node = &nodes[i];
+        let segments = node.path.segments();
+        let depth = segments.len() - 1;
+        let segment = segments[depth];
+        close_blocks(out, &mut open, depth)?;
+        if depth == 0 {
+            writeln!(out)?;
+        }
+        let indent = "    ".repeat(depth);
+
+        if node.section.get::<Section11Entry>().is_some() {
+            let mut end = i + 1;
+            while end < nodes.len() && is_next_slot(&nodes[end - 1], &nodes[end]) {
+                end += 1;
+            }
+            writeln!(out, "{}Section11 @0x{:X} {{", indent, node.offset)?;
+            for slot in &nodes[i..end] {
+                if let Some(entry) = slot.section.get::<Section11Entry>() {
+                    let index = slot.path.segments()[depth].index.unwrap_or_default();
+                    writeln!(
+                        out,
+                        "{}    {}  # [{}] 0x{:08X}",
+                        indent,
+                        entry.value(),
+                        index,
+                        entry.data
+                    )?;
+                }
+            }
+            writeln!(out, "{}}}", indent)?;
+            i = end;
+            continue;
+        }
+
+        if let Some(role) = role(segment) {
+            writeln!(out, "{}# {}", indent, role)?;
+        }
+        writeln!(out, "{}{} @0x{:X} {{", indent, segment, node.offset)?;
+        let bytes = node.section.as_bytes();
+        for span in node.section.layout() {
+            writeln!(
+                out,
+                "{}    {} = {}",
+                indent,
+                span.name,
+                literal(node.section.kind(), span, &bytes[span.range()])
+            )?;
+        }
+        open += 1;
+        i += 1;
+    }
+    close_blocks(out, &mut open, 0)?;
+
+    write_unclaimed(out, data, nodes)?;
+    writeln!(out, "size 0x{:X}", data.len())
+}
+
+/// Closes open blocks until only `depth` of them remain
+fn close_blocks(out: &mut impl Write, open: &mut usize, depth: usize) -> std::fmt::Result {
+    while *open > depth {
+        *open -= 1;
+        writeln!(out, "{}}}", "    ".repeat(*open))?;
+    }
+    Ok(())
+}
+
+/// True if `next` is the Section11 slot directly after `prev` under the same parent
+fn is_next_slot(prev: &Node, next: &Node) -> bool {
+    let (prev_segments, next_segments) = (prev.path.segments(), next.path.segments());
+    next.section.get::<Section11Entry>().is_some()
+        && prev_segments.len() == next_segments.len()
+        && prev_segments[..prev_segments.len() - 1] == next_segments[..next_segments.len() - 1]
+        && next.offset == prev.offset + 4
+}
+
+fn literal(kind: &str, span: &FieldSpan, bytes: &[u8]) -> String {
+    if span.name == "magic" {
+        return string_literal(bytes);
+    }
+    let mut le = [0u8; 8];
+    le[..bytes.len()].copy_from_slice(bytes);
+    let value = u64::from_le_bytes(le);
+    if let Some((_, name)) = value_names(kind, span.name)
+        .iter()
+        .find(|(named, _)| *named == value)
+    {
+        name.to_string()
+    } else if is_offset_field(span.name) {
+        format!("0x{:X}", value)
+    } else {
+        value.to_string()
+    }
+}
+
+fn string_literal(bytes: &[u8]) -> String {
+    let mut literal = String::from("\"");
+    for &byte in bytes {
+        match byte {
+            0 => literal.push_str("\\0"),
+            b'"' | b'\\' => {
+                literal.push('\\');
+                literal.push(byte as char);
+            }
+            0x20..=0x7E => literal.push(byte as char),
+            _ => literal.push_str(&format!("\\x{:02X}", byte)),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+/// Writes `raw` blocks for the non-zero bytes that aren't part of any struct
+fn write_unclaimed(out: &mut impl Write, data: &[u8], nodes: &[Node]) -> std::fmt::Result {
+    let mut claimed = vec![false; data.len()];
+    for node in nodes {
+        let end = node.offset + node.section.as_bytes().len();
+        claimed[node.offset..end].fill(true);
+    }
+
+    let mut start = 0;
+    while start < data.len() {
+        if claimed[start] {
+            start += 1;
+            continue;
+        }
+        let end = (start..data.len())
+            .find(|&i| claimed[i])
+            .unwrap_or(data.len());
+        let run = &data[start..end];
+        if let (Some(first), Some(last)) = (
+            run.iter().position(|&b| b != 0),
+            run.iter().rposition(|&b| b != 0),
+        ) {
+            writeln!(out, "\nraw @0x{:X} {{", start + first)?;
+            for line in run[first..=last].chunks(16) {
+                let hex: Vec<String> = line.iter().map(|b| format!("{:02X}", b)).collect();
+                writeln!(out, "    {}", hex.join(" "))?;
+            }
+            writeln!(out, "}}")?;
+        }
+        start = end;
+    }
+    Ok(())
+}
@@ -0,0 +1,51 @@
+pub mod assemble;
+pub mod disassemble;
+
+use super::walk::PathSegment;
+
+/// What a block is for, printed as a comment above it
+fn role(segment: PathSegment) -> Option<&'static str> {
+    match (segment.section, segment.index.is_some()) {
+        (0, false) => Some("header"),
+        (1, false) => Some("state machine"),
+        (4, false) => Some("node tree"),
+        (6, true) => Some("action"),
+        (7, false) => Some("properties"),
+        _ => None,
+    }
+}
+
+/// `(value, name)` pairs of one field
+type ValueNames = &'static [(u64, &'static str)];
+
+/// Names for the values of enum-like fields, by struct and field. Values without a name are
+/// written as numbers.
+const NAMED_VALUES: &[(&str, &str, ValueNames)] = &[
+    // Dark Souls III writes version 4; Sekiro, Elden Ring and Armored Core VI all write 5
+    ("Header", "version", &[(4, "DarkSouls3"), (5, "Sekiro")]),
+    // The action a Section6 entry performs, here the ones that draw something
+    (
+        "Section6Entry",
+        "unk00",
+        &[
+            (600, "PointSprite"),
+            (601, "Line"),
+            (602, "QuadLine"),
+            (603, "BillboardEx"),
+            (604, "MultiTextureBillboardEx"),
+            (605, "Model"),
+            (606, "Tracer"),
+            (607, "Distortion"),
+            (608, "RadialBlur"),
+            (609, "PointLight"),
+        ],
+    ),
+];
+
+/// The named values of `kind.field`, empty when it has none
+fn value_names(kind: &str, field: &str) -> ValueNames {
+    NAMED_VALUES
+        .iter()
+        .find(|(k, f, _)| *k == kind && *f == field)
+        .map_or(&[], |(_, _, names)| names)
+}
@@ -0,0 +1,168 @@
+use super::layout::FieldSpan;
+#[doc(hidden)]
+pub use inventory;
+use serde::Serialize;
+use serde_reflection::{Format, Samples, Tracer};
+#[doc(hidden)]
+pub use validator;
+use validator::{ValidationError, ValidationErrors};
+use zerocopy::FromBytes;
+
+/// Everything known about one section struct, registered by `#[derive(FxrSection)]`
+#[derive(Debug)]
+pub struct SectionInfo {
+    /// Struct name, e.g. `Section6Entry`
+    pub name: &'static str,
+    /// Section number the struct is found under in the parsed tree, `0` for the header. `None`
+    /// for structs the parser doesn't read.
+    pub section: Option<u8>,
+    /// Whether the section is an array of the struct rather than a single one
+    pub array: bool,
+    /// Size of the struct in bytes
+    pub size: usize,
+    pub fields: &'static [FieldSpan],
+    /// Runs [`ValidateSection::validate_section`] on the raw bytes of one struct
+    pub validate: fn(&[u8]) -> Result<(), ValidationErrors>,
+    /// Records the serde format of the struct, see [`trace_zeroed`]
+    pub trace: fn(&mut Tracer, &mut Samples) -> serde_reflection::Result<Format>,
+    /// Offset fields the parser follows to reach nested structs, in declaration order
+    pub pointers: &'static [Pointer],
+    /// The `#[validate(...)]` rules on individual fields, in declaration order
+    pub rules: &'static [FieldValidation],
+}
+
+impl SectionInfo {
+    /// The `#[validate(...)]` rule on a field, e.g. `range(min = 0, max = 0)`
+    pub fn rule(&self, field: &str) -> Option<&'static str> {
+        self.rules
+            .iter()
+            .find(|rule| rule.field == field)
+            .map(|rule| rule.rule)
+    }
+}
+
+/// An `*_offset`/`*_count` pair that leads from one struct to others, declared with
+/// `#[fxr(points_to = "...", count = "...")]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pointer {
+    /// Field holding the absolute offset, e.g. `section8_offset`
+    pub field: &'static str,
+    /// Field holding the number of structs, e.g. `section8_count`
+    pub count: &'static str,
+    /// Struct type found at the offset
+    pub target: &'static str,
+    /// One struct is read whenever the count is non-zero, instead of `count` of them
+    pub single: bool,
+}
+
+/// The source of a field's `#[validate(...)]` attribute, kept so tools can show what a field is
+/// checked against. `validator` doesn't expose its rules at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldValidation {
+    pub field: &'static str,
+    /// Attribute contents, e.g. `range(min = 0, max = 0)`
+    pub rule: &'static str,
+}
+
+inventory::collect!(SectionInfo);
+
+/// Validation hook generated for every section struct.
+///
+/// Structs marked `#[fxr(validate)]` run their `#[validate]` rules; the rest accept any value.
+pub trait ValidateSection {
+    fn validate_section(&self) -> Result<(), ValidationErrors>;
+}
+
+/// Copies a struct out of its raw bytes, which need not be aligned, and validates it
+pub fn validate_bytes<T>(bytes: &[u8]) -> Result<(), ValidationErrors>
+where
+    T: FromBytes + ValidateSection,
+{
+    match T::read_from_bytes(bytes) {
+        Ok(value) => value.validate_section(),
+        Err(_) => {
+            let mut errors = ValidationErrors::new();
+            let mut error = ValidationError::new("size");
+            error.message = Some(
+                format!(
+                    "expected {} bytes, got {}",
+                    std::mem::size_of::<T>(),
+                    bytes.len()
+                )
+                .into(),
+            );
+            errors.add("__all__", error);
+            Err(errors)
+        }
+    }
+}
+
+/// Traces the serde format of a section struct by serializing an all-zero instance, which works
+/// for structs that only implement `Serialize`
+pub fn trace_zeroed<T>(
+    tracer: &mut Tracer,
+    samples: &mut Samples,
+) -> serde_reflection::Result<Format>
+where
+    T: FromBytes + Serialize,
+{
+    tracer
+        .trace_value(samples, &T::new_zeroed())
+        .map(|(format, _)| format)
+}
+
+/// Every registered section struct, in section order (`Header`, `Section1Container`, ...,
+/// `Section14Entry`).
+///
+/// # Example
+/// ```rust
+/// use fxr_binary_reader::fxr::catalog::{section, sections};
+///
+/// assert_eq!(sections()[0].name, "Header");
+///
+/// let entry = section("Section9Entry").unwrap();
+/// assert_eq!(entry.size, 0x18);
+/// assert_eq!(entry.fields[2].name, "section11_count");
+/// assert!((entry.validate)(&[0u8; 0x18]).is_ok());
+/// assert_eq!(entry.pointers[0].target, "Section11Entry");
+///
+/// let header = section("Header").unwrap();
+/// assert_eq!(header.rule("version"), Some("range(min = 4, max = 5)"));
+/// assert_eq!(header.rule("ffx_id"), None);
+/// ```
+pub fn sections() -> Vec<&'static SectionInfo> {
+    let mut sections: Vec<&'static SectionInfo> = inventory::iter::<SectionInfo>().collect();
+    sections.sort_by_key(|info| (section_number(info.name), info.name));
+    sections
+}
+
+/// Looks up a registered section struct by name
+pub fn section(name: &str) -> Option<&'static SectionInfo> {
+    inventory::iter::<SectionInfo>().find(|info| info.name == name)
+}
+
+/// Looks up the struct found at a section of the parsed tree, e.g. `(6, true)` for
+/// `Section6[i]`
+///
+/// # Example
+/// ```rust
+/// use fxr_binary_reader::fxr::catalog::section_at;
+///
+/// assert_eq!(section_at(4, false).unwrap().name, "Section4Container");
+/// assert_eq!(section_at(4, true).unwrap().name, "Section4Entry");
+/// assert!(section_at(8, false).is_none());
+/// ```
+pub fn section_at(section: u8, array: bool) -> Option<&'static SectionInfo> {
+    inventory::iter::<SectionInfo>()
+        .find(|info| info.section == Some(section) && info.array == array)
+}
+
+/// `Section11Entry` -> 11, `Header` -> 0
+fn section_number(name: &str) -> u32 {
+    name.chars()
+        .skip_while(|c| !c.is_ascii_digit())
+        .take_while(char::is_ascii_digit)
+        .collect::<String>()
+        .parse()
+        .unwrap_or(0)
+}
@@ -0,0 +1,277 @@
+use super::{
+    Section11Entry, Section11Value, fxr_parser_with_sections::ParsedFXR, layout::FieldSpan,
+    walk::walk,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Options for [`Census::report`]
+#[derive(Debug, Clone, Copy)]
+pub struct CensusOptions {
+    /// How many of the most frequent values to list per field
+    pub top_values: usize,
+    /// Smallest absolute correlation coefficient worth reporting
+    pub min_correlation: f64,
+}
+
+impl Default for CensusOptions {
+    fn default() -> Self {
+        Self {
+            top_values: 8,
+            min_correlation: 0.9,
+        }
+    }
+}
+
+/// Collects every field value of every struct across many files, grouped by struct type.
+///
+/// # Example
+/// ```rust
+/// use fxr_binary_reader::fxr::{
+///     census::{Census, CensusOptions},
+///     fxr_parser_with_sections::parse_fxr,
+/// };
+///
+/// let mut data = vec![0u8; 0xA0];
+/// data[0..4].copy_from_slice(b"FXR\0");
+/// data[6..8].copy_from_slice(&5u16.to_le_bytes()); // version
+/// data[8..12].copy_from_slice(&1u32.to_le_bytes()); // unk08
+/// data[0x10..0x14].copy_from_slice(&0x90u32.to_le_bytes()); // section1_offset
+/// data[0x14..0x18].copy_from_slice(&1u32.to_le_bytes()); // section1_count
+/// data[0x68..0x6C].copy_from_slice(&1u32.to_le_bytes()); // unk68
+/// let mut other = data.clone();
+/// other[6..8].copy_from_slice(&4u16.to_le_bytes());
+///
+/// let mut census = Census::new();
+/// census.add(&parse_fxr(&data).unwrap());
+/// census.add(&parse_fxr(&other).unwrap());
+///
+/// let report = census.report(CensusOptions::default());
+/// assert_eq!(report.files, 2);
+/// let header = &report.sections[0];
+/// assert_eq!(header.kind, "Header");
+/// let version = header.fields.iter().find(|f| f.name == "version").unwrap();
+/// assert_eq!((version.distinct, version.min, version.max), (2, 4, 5));
+/// ```
+#[derive(Debug, Default)]
+pub struct Census {
+    files: usize,
+    /// Struct types in the order they were first seen, so the report follows the tree
+    sections: Vec<Columns>,
+    index: HashMap<&'static str, usize>,
+}
+
+/// Every value of every field of one struct type, stored column by column
+#[derive(Debug)]
+struct Columns {
+    kind: &'static str,
+    layout: &'static [FieldSpan],
+    values: Vec<Vec<u64>>,
+    /// Raw bytes of the first struct seen, as a realistic starting point for probing
+    sample: Vec<u8>,
+}
+
+impl Census {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds every struct of a parsed file
+    pub fn add(&mut self, fxr: &ParsedFXR) {
+        self.files += 1;
+        for node in walk(fxr) {
+            let kind = node.section.kind();
+            let layout = node.section.layout();
+            let index = *self.index.entry(kind).or_insert_with(|| {
+                self.sections.push(Columns {
+                    kind,
+                    layout,
+                    values: vec![Vec::new(); layout.len()],
+                    sample: node.section.as_bytes().to_vec(),
+                });
+                self.sections.len() - 1
+            });
+            for (column, field) in self.sections[index]
+                .values
+                .iter_mut()
+                .zip(node.section.reflect())
+            {
+                column.push(field.value().as_u64());
+            }
+        }
+    }
+
+    /// Struct types seen so far with their layouts, in the order they were first seen
+    pub fn kinds(&self) -> impl Iterator<Item = (&'static str, &'static [FieldSpan])> + '_ {
+        self.sections
+            .iter()
+            .map(|columns| (columns.kind, columns.layout))
+    }
+
+    /// Every collected value of one field, read as an unsigned little-endian integer
+    pub fn values(&self, kind: &str, field: &str) -> Option<&[u64]> {
+        let columns = &self.sections[*self.index.get(kind)?];
+        let position = columns.layout.iter().position(|span| span.name == field)?;
+        Some(&columns.values[position])
+    }
+
+    /// Raw bytes of the first struct of this type that was added
+    pub fn sample(&self, kind: &str) -> Option<&[u8]> {
+        Some(&self.sections[*self.index.get(kind)?].sample)
+    }
+
+    /// Summarizes the collected values
+    pub fn report(&self, options: CensusOptions) -> CensusReport {
+        CensusReport {
+            files: self.files,
+            sections: self
+                .sections
+                .iter()
+                .map(|columns| columns.report(options))
+                .collect(),
+        }
+    }
+}
+
+impl Columns {
+    fn report(&self, options: CensusOptions) -> SectionReport {
+        let fields = self
+            .layout
+            .iter()
+            .zip(&self.values)
+            .enumerate()
+            .map(|(i, (span, values))| {
+                let mut counts: HashMap<u64, usize> = HashMap::new();
+                for value in values {
+                    *counts.entry(*value).or_default() += 1;
+                }
+                let mut top_values: Vec<ValueCount> = counts
+                    .iter()
+                    .map(|(&value, &count)| ValueCount { value, count })
+                    .collect();
+                top_values.sort_by(|a, b| b.count.cmp(&a.count).then(a.value.cmp(&b.value)));
+                top_values.truncate(options.top_values);
+
+                let non_zero = values.iter().filter(|value| **value != 0).count();
+                let floats = values
+                    .iter()
+                    .filter(|value| span.size == 4 && looks_like_float(**value))
+                    .count();
+
+                FieldReport {
+                    name: span.name,
+                    offset: span.offset,
+                    size: span.size,
+                    distinct: counts.len(),
+                    min: values.iter().copied().min().unwrap_or_default(),
+                    max: values.iter().copied().max().unwrap_or_default(),
+                    float_like: if non_zero == 0 {
+                        0.0
+                    } else {
+                        floats as f64 / non_zero as f64
+                    },
+                    top_values,
+                    correlations: self.correlations(i, options.min_correlation),
+                }
+            })
+            .collect();
+
+        SectionReport {
+            kind: self.kind,
+            records: self.values.first().map_or(0, Vec::len),
+            fields,
+        }
+    }
+
+    /// Other fields whose values move together with field `i`
+    fn correlations(&self, i: usize, min_correlation: f64) -> Vec<Correlation> {
+        let mut correlations: Vec<Correlation> = self
+            .layout
+            .iter()
+            .zip(&self.values)
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .filter_map(|(_, (span, values))| {
+                let coefficient = pearson(&self.values[i], values)?;
+                (coefficient.abs() >= min_correlation).then_some(Correlation {
+                    field: span.name,
+                    coefficient,
+                })
+            })
+            .collect();
+        correlations.sort_by(|a, b| b.coefficient.abs().total_cmp(&a.coefficient.abs()));
+        correlations
+    }
+}
+
+/// Same heuristic as [`Section11Entry::value`]
+fn looks_like_float(value: u64) -> bool {
+    let entry = Section11Entry { data: value as u32 };
+    matches!(entry.value(), Section11Value::Float(_))
+}
+
+/// Pearson correlation coefficient, or `None` if either column is constant
+fn pearson(a: &[u64], b: &[u64]) -> Option<f64> {
+    let n = a.len() as f64;
+    if a.len() < 2 {
+        return None;
+    }
+    let mean_a = a.iter().map(|&x| x as f64).sum::<f64>() / n;
+    let mean_b = b.iter().map(|&x| x as f64).sum::<f64>() / n;
+    let (mut covariance, mut variance_a, mut variance_b) = (0.0, 0.0, 0.0);
+    for (&x, &y) in a.iter().zip(b) {
+        let (dx, dy) = (x as f64 - mean_a, y as f64 - mean_b);
+        covariance += dx * dy;
+        variance_a += dx * dx;
+        variance_b += dy * dy;
+    }
+    if variance_a == 0.0 || variance_b == 0.0 {
+        return None;
+    }
+    Some(covariance / (variance_a * variance_b).sqrt())
+}
+
+/// Output of [`Census::report`]
+#[derive(Debug, Clone, Serialize)]
+pub struct CensusReport {
+    pub files: usize,
+    pub sections: Vec<SectionReport>,
+}
+
+/// Statistics for one struct type
+#[derive(Debug, Clone, Serialize)]
+pub struct SectionReport {
+    pub kind: &'static str,
+    /// Number of structs of this type across all files
+    pub records: usize,
+    pub fields: Vec<FieldReport>,
+}
+
+/// Statistics for one field, with values read as unsigned little-endian integers
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldReport {
+    pub name: &'static str,
+    pub offset: usize,
+    pub size: usize,
+    pub distinct: usize,
+    pub min: u64,
+    pub max: u64,
+    /// Share of the non-zero values that read as a plausible `f32`
+    pub float_like: f64,
+    /// Most frequent values, most common first
+    pub top_values: Vec<ValueCount>,
+    /// Fields of the same struct that correlate with this one, strongest first
+    pub correlations: Vec<Correlation>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ValueCount {
+    pub value: u64,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Correlation {
+    pub field: &'static str,
+    pub coefficient: f64,
+}
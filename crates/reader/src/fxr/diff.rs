@@ -0,0 +1,167 @@
+use super::{
+    fxr_parser_with_sections::ParsedFXR,
+    walk::{Node, SectionPath, is_offset_field, walk},
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter},
+};
+
+/// A single difference between two parsed files
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum Change {
+    /// A struct only present in the new file. Its descendants are not listed separately.
+    Added {
+        path: SectionPath,
+        kind: &'static str,
+    },
+    /// A struct only present in the old file. Its descendants are not listed separately.
+    Removed {
+        path: SectionPath,
+        kind: &'static str,
+    },
+    /// A field whose value differs between the two files
+    Changed {
+        path: SectionPath,
+        field: String,
+        old: Value,
+        new: Value,
+    },
+}
+
+impl Change {
+    pub fn path(&self) -> &SectionPath {
+        match self {
+            Change::Added { path, .. } | Change::Removed { path, .. } => path,
+            Change::Changed { path, .. } => path,
+        }
+    }
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Change::Added { path, kind } => write!(f, "+ {} ({})", path, kind),
+            Change::Removed { path, kind } => write!(f, "- {} ({})", path, kind),
+            Change::Changed {
+                path,
+                field,
+                old,
+                new,
+            } => write!(f, "~ {}.{}: {} -> {}", path, field, old, new),
+        }
+    }
+}
+
+/// Options controlling which differences are reported
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DiffOptions {
+    /// Skip `*_offset` fields, which change whenever data before them grows or shrinks
+    pub ignore_offsets: bool,
+}
+
+/// Compares two parsed files structurally.
+///
+/// Structs are aligned by their [`SectionPath`] rather than their byte offset, so inserting data
+/// in the middle of a file shows up as the nodes that were added instead of a cascade of
+/// shifted bytes.
+///
+/// # Returns
+/// The changes ordered by section path.
+///
+/// # Example
+/// ```rust
+/// use fxr_binary_reader::fxr::{
+///     diff::{Change, DiffOptions, diff},
+///     fxr_parser_with_sections::parse_fxr,
+/// };
+///
+/// let mut data = vec![0u8; 0xA0];
+/// data[0..4].copy_from_slice(b"FXR\0");
+/// data[6..8].copy_from_slice(&5u16.to_le_bytes()); // version
+/// data[8..12].copy_from_slice(&1u32.to_le_bytes()); // unk08
+/// data[0x10..0x14].copy_from_slice(&0x90u32.to_le_bytes()); // section1_offset
+/// data[0x14..0x18].copy_from_slice(&1u32.to_le_bytes()); // section1_count
+/// data[0x68..0x6C].copy_from_slice(&1u32.to_le_bytes()); // unk68
+/// let mut changed = data.clone();
+/// changed[0x0C..0x10].copy_from_slice(&302421u32.to_le_bytes()); // ffx_id
+///
+/// let old = parse_fxr(&data).unwrap();
+/// let new = parse_fxr(&changed).unwrap();
+/// let changes = diff(&old, &new, DiffOptions::default());
+/// assert_eq!(changes.len(), 1);
+/// assert_eq!(changes[0].to_string(), "~ Header.ffx_id: 0 -> 302421");
+/// ```
+pub fn diff(old: &ParsedFXR, new: &ParsedFXR, options: DiffOptions) -> Vec<Change> {
+    let old_nodes = by_path(walk(old));
+    let new_nodes = by_path(walk(new));
+    let mut changes = Vec::new();
+
+    for (path, old_node) in &old_nodes {
+        match new_nodes.get(path) {
+            Some(new_node) => changes.extend(field_changes(old_node, new_node, options)),
+            None => changes.push(Change::Removed {
+                path: path.clone(),
+                kind: old_node.section.kind(),
+            }),
+        }
+    }
+    for (path, new_node) in &new_nodes {
+        if !old_nodes.contains_key(path) {
+            changes.push(Change::Added {
+                path: path.clone(),
+                kind: new_node.section.kind(),
+            });
+        }
+    }
+
+    changes.sort_by(|a, b| a.path().cmp(b.path()));
+    remove_nested_additions(changes)
+}
+
+fn by_path(nodes: Vec<Node>) -> BTreeMap<SectionPath, Node> {
+    nodes
+        .into_iter()
+        .map(|node| (node.path.clone(), node))
+        .collect()
+}
+
+fn field_changes(old: &Node, new: &Node, options: DiffOptions) -> Vec<Change> {
+    let new_fields: BTreeMap<String, Value> = new.section.fields().into_iter().collect();
+    old.section
+        .fields()
+        .into_iter()
+        .filter(|(field, _)| !(options.ignore_offsets && is_offset_field(field)))
+        .filter_map(|(field, old_value)| {
+            let new_value = new_fields.get(&field)?;
+            (*new_value != old_value).then(|| Change::Changed {
+                path: old.path.clone(),
+                field,
+                old: old_value,
+                new: new_value.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Drops additions and removals whose parent was itself added or removed
+fn remove_nested_additions(changes: Vec<Change>) -> Vec<Change> {
+    let mut kept: Vec<Change> = Vec::with_capacity(changes.len());
+    let mut subtree: Option<SectionPath> = None;
+    for change in changes {
+        match &change {
+            Change::Added { path, .. } | Change::Removed { path, .. } => {
+                if subtree.as_ref().is_some_and(|root| path.starts_with(root)) {
+                    continue;
+                }
+                subtree = Some(path.clone());
+            }
+            Change::Changed { .. } => {}
+        }
+        kept.push(change);
+    }
+    kept
+}
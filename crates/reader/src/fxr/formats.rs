@@ -0,0 +1,445 @@
+use super::{
+    Header, Section1Container, Section2Container, Section3Entry, Section4Container, Section4Entry,
+    Section5Entry, Section6Entry, Section7Container, Section8Entry, Section9Entry,
+    Section10Container, Section11Entry, Section12Entry, Section13Entry, Section14Entry,
+    catalog::sections, fxr_parser_with_sections::parse_fxr_unchecked, writer::FxrWriter,
+};
+use serde_json::Value;
+use serde_reflection::{ContainerFormat, Format, Registry, Samples, Tracer, TracerConfig};
+use std::fmt::Write;
+use zerocopy::FromZeros;
+
+/// Traces the serde format of every section struct and of the parsed tree around them
+/// (`ParsedFXR`, `ParsedSections`, `ParsedSection4Tree` and the `ParsedSection6` to
+/// `ParsedSection9` wrappers) into one registry, keyed by type name.
+///
+/// The formats describe the JSON written by `fxr dump --format json`, whose `fxr` member is a
+/// `ParsedFXR`. Offsets are serialized as `"0x1A0"` strings, so they show up as `Str` rather than
+/// `U32`; `fxr query` and `fxr export` write offsets the same way.
+///
+/// # Example
+/// ```rust
+/// use fxr_binary_reader::fxr::formats::registry;
+/// use serde_reflection::{ContainerFormat, Format};
+///
+/// let registry = registry().unwrap();
+/// let ContainerFormat::Struct(fields) = &registry["Section9Entry"] else {
+///     panic!("Section9Entry is a struct");
+/// };
+/// assert_eq!(fields[2].name, "section11_count");
+/// assert_eq!(fields[2].value, Format::U32);
+/// let ContainerFormat::Struct(fields) = &registry["Header"] else {
+///     panic!("Header is a struct");
+/// };
+/// assert_eq!(fields[0].value, Format::Str); // magic, written as "FXR\0"
+/// let ContainerFormat::Struct(fields) = &registry["ParsedFXR"] else {
+///     panic!("ParsedFXR is a struct");
+/// };
+/// assert_eq!(fields[0].value, Format::TypeName("Header".into()));
+/// assert_eq!(
+///     fields[1].value,
+///     Format::Option(Box::new(Format::TypeName("ParsedSections".into())))
+/// );
+/// ```
+pub fn registry() -> serde_reflection::Result<Registry> {
+    let mut tracer = Tracer::new(TracerConfig::default());
+    let mut samples = Samples::new();
+    for info in sections() {
+        (info.trace)(&mut tracer, &mut samples)?;
+    }
+    let bytes = sample_file();
+    let fxr = parse_fxr_unchecked(&bytes).expect("the sample file has every section in bounds");
+    tracer.trace_value(&mut samples, &fxr)?;
+    tracer.registry()
+}
+
+/// Checks that a JSON value has the shape `format` describes, e.g. that a line of
+/// `fxr dump --format json` matches the traced `ParsedFXR`.
+///
+/// # Arguments
+/// * `registry` - Formats from [`registry`]
+/// * `format` - Expected format, usually `Format::TypeName` of a registered type
+/// * `value` - The JSON to check
+///
+/// # Returns
+/// The JSON path of the first mismatch and what was expected there
+///
+/// # Example
+/// ```rust
+/// use fxr_binary_reader::fxr::{
+///     formats::{check_json, registry},
+///     fxr_parser_with_sections::parse_fxr,
+/// };
+/// use serde_json::json;
+/// use serde_reflection::Format;
+///
+/// let mut bytes = vec![0u8; 0xE0];
+/// bytes[0..4].copy_from_slice(b"FXR\0");
+/// bytes[6..8].copy_from_slice(&5u16.to_le_bytes()); // version
+/// bytes[8..12].copy_from_slice(&1u32.to_le_bytes()); // unk08
+/// bytes[0x10..0x14].copy_from_slice(&0x90u32.to_le_bytes()); // section1_offset
+/// bytes[0x14..0x18].copy_from_slice(&1u32.to_le_bytes()); // section1_count
+/// bytes[0x28..0x2C].copy_from_slice(&0xB0u32.to_le_bytes()); // section4_offset
+/// bytes[0x2C..0x30].copy_from_slice(&1u32.to_le_bytes()); // section4_count
+/// bytes[0x68..0x6C].copy_from_slice(&1u32.to_le_bytes()); // unk68
+/// bytes[0x94..0x98].copy_from_slice(&1u32.to_le_bytes()); // section2_count
+/// bytes[0x98..0x9C].copy_from_slice(&0xA0u32.to_le_bytes()); // section2_offset
+///
+/// // What `fxr dump --format json` prints for this file, read back as JSON
+/// let fxr = parse_fxr(&bytes).unwrap();
+/// let line = serde_json::to_string(&json!({ "path": "a.fxr", "fxr": fxr })).unwrap();
+/// let dump: serde_json::Value = serde_json::from_str(&line).unwrap();
+///
+/// let registry = registry().unwrap();
+/// let parsed_fxr = Format::TypeName("ParsedFXR".into());
+/// assert_eq!(check_json(&registry, &parsed_fxr, &dump["fxr"]), Ok(()));
+/// assert_eq!(dump["fxr"]["section4_tree"]["container"]["section6_offset"], "0x0");
+///
+/// let mut broken = dump["fxr"].clone();
+/// broken["header"]["section1_offset"] = json!(0x90);
+/// assert_eq!(
+///     check_json(&registry, &parsed_fxr, &broken),
+///     Err("header.section1_offset: expected Str, got 144".to_string())
+/// );
+///
+/// let mut broken = dump["fxr"].clone();
+/// broken["section4_tree"]["container"]["section6_offset"] = json!(0);
+/// assert_eq!(
+///     check_json(&registry, &parsed_fxr, &broken),
+///     Err("section4_tree.container.section6_offset: expected Str, got 0".to_string())
+/// );
+/// ```
+pub fn check_json(registry: &Registry, format: &Format, value: &Value) -> Result<(), String> {
+    check(registry, format, value, "")
+}
+
+fn check(registry: &Registry, format: &Format, value: &Value, path: &str) -> Result<(), String> {
+    let mismatch = || match path.trim_start_matches('.') {
+        "" => format!("expected {:?}, got {}", format, value),
+        at => format!("{}: expected {:?}, got {}", at, format, value),
+    };
+    let unsigned = |max: u64| value.as_u64().is_some_and(|n| n <= max);
+    let signed = |min: i64, max: i64| value.as_i64().is_some_and(|n| (min..=max).contains(&n));
+    let ok = match format {
+        Format::TypeName(name) => {
+            let Some(ContainerFormat::Struct(fields)) = registry.get(name) else {
+                return Err(format!("`{}` is not a traced struct", name));
+            };
+            let Value::Object(object) = value else {
+                return Err(mismatch());
+            };
+            if object.len() != fields.len() {
+                return Err(mismatch());
+            }
+            for field in fields {
+                let field_path = format!("{}.{}", path, field.name);
+                let Some(field_value) = object.get(&field.name) else {
+                    return Err(format!("{}: missing", field_path.trim_start_matches('.')));
+                };
+                check(registry, &field.value, field_value, &field_path)?;
+            }
+            true
+        }
+        Format::Option(inner) => {
+            return match value {
+                Value::Null => Ok(()),
+                value => check(registry, inner, value, path),
+            };
+        }
+        Format::Seq(inner) => {
+            let Value::Array(items) = value else {
+                return Err(mismatch());
+            };
+            for (i, item) in items.iter().enumerate() {
+                check(registry, inner, item, &format!("{}[{}]", path, i))?;
+            }
+            true
+        }
+        Format::Bool => value.is_boolean(),
+        Format::U8 => unsigned(u8::MAX.into()),
+        Format::U16 => unsigned(u16::MAX.into()),
+        Format::U32 => unsigned(u32::MAX.into()),
+        Format::U64 => unsigned(u64::MAX),
+        Format::I8 => signed(i8::MIN.into(), i8::MAX.into()),
+        Format::I16 => signed(i16::MIN.into(), i16::MAX.into()),
+        Format::I32 => signed(i32::MIN.into(), i32::MAX.into()),
+        Format::I64 => signed(i64::MIN, i64::MAX),
+        Format::F32 | Format::F64 => value.is_number(),
+        Format::Char | Format::Str => value.is_string(),
+        _ => false,
+    };
+    match ok {
+        true => Ok(()),
+        false => Err(mismatch()),
+    }
+}
+
+/// A file with one of every struct in the tree, so tracing it leaves no `Option` or `Vec` of
+/// unknown type
+fn sample_file() -> Vec<u8> {
+    let mut next = size_of::<Header>();
+    let mut place = |size: usize| {
+        let offset = next as u32;
+        next += size;
+        offset
+    };
+    let section1 = place(size_of::<Section1Container>());
+    let section2 = place(size_of::<Section2Container>());
+    let section3 = place(size_of::<Section3Entry>());
+    let section4 = place(size_of::<Section4Container>());
+    let section4_entry = place(size_of::<Section4Entry>());
+    let section5 = place(size_of::<Section5Entry>());
+    let section6 = place(size_of::<Section6Entry>());
+    let section7 = place(size_of::<Section7Container>());
+    let section8 = place(size_of::<Section8Entry>());
+    let section9 = place(size_of::<Section9Entry>());
+    let section10 = place(size_of::<Section10Container>());
+    let section11 = place(size_of::<Section11Entry>());
+    let section12 = place(size_of::<Section12Entry>());
+    let section13 = place(size_of::<Section13Entry>());
+    let section14 = place(size_of::<Section14Entry>());
+
+    let mut writer = FxrWriter::new();
+    let header = Header {
+        section1_offset: section1,
+        section1_count: 1,
+        section4_offset: section4,
+        section4_count: 1,
+        section12_offset: section12,
+        section12_count: 1,
+        section13_offset: section13,
+        section13_count: 1,
+        section14_offset: section14,
+        section14_count: 1,
+        ..Header::default()
+    };
+    writer.write_struct(0, &header);
+    let section1_container = Section1Container {
+        section2_offset: section2,
+        section2_count: 1,
+        ..FromZeros::new_zeroed()
+    };
+    writer.write_struct(section1 as usize, &section1_container);
+    let section2_container = Section2Container {
+        section3_offset: section3,
+        section3_count: 1,
+        ..FromZeros::new_zeroed()
+    };
+    writer.write_struct(section2 as usize, &section2_container);
+    let section4_container = Section4Container {
+        section4_offset: section4_entry,
+        section4_count: 1,
+        section5_offset: section5,
+        section5_count: 1,
+        section6_offset: section6,
+        section6_count: 1,
+        ..FromZeros::new_zeroed()
+    };
+    writer.write_struct(section4 as usize, &section4_container);
+    let section6_entry = Section6Entry {
+        section11_offset: section11,
+        section11_count1: 1,
+        section10_offset: section10,
+        section10_count: 1,
+        section7_offset: section7,
+        section7_count1: 1,
+        ..FromZeros::new_zeroed()
+    };
+    writer.write_struct(section6 as usize, &section6_entry);
+    let section7_container = Section7Container {
+        section11_offset: section11,
+        section11_count: 1,
+        section8_offset: section8,
+        section8_count: 1,
+        ..FromZeros::new_zeroed()
+    };
+    writer.write_struct(section7 as usize, &section7_container);
+    let section8_entry = Section8Entry {
+        section11_offset: section11,
+        section11_count: 1,
+        section9_offset: section9,
+        section9_count: 1,
+        ..FromZeros::new_zeroed()
+    };
+    writer.write_struct(section8 as usize, &section8_entry);
+    let section9_entry = Section9Entry {
+        section11_offset: section11,
+        section11_count: 1,
+        ..FromZeros::new_zeroed()
+    };
+    writer.write_struct(section9 as usize, &section9_entry);
+    let section10_container = Section10Container {
+        section11_offset: section11,
+        section11_count: 1,
+        ..FromZeros::new_zeroed()
+    };
+    writer.write_struct(section10 as usize, &section10_container);
+    writer.pad_to(next);
+    writer.into_bytes()
+}
+
+/// C# classes mirroring the registry, with `System.Text.Json` attributes so they deserialize the
+/// JSON output directly.
+///
+/// # Arguments
+/// * `registry` - Formats from [`registry`]
+/// * `namespace` - Namespace the classes are declared in
+///
+/// # Example
+/// ```rust
+/// use fxr_binary_reader::fxr::formats::{csharp_classes, registry};
+///
+/// let source = csharp_classes(&registry().unwrap(), "Fxr");
+/// assert!(source.contains("    public sealed class Section9Entry\n    {\n"));
+/// assert!(source.contains(
+///     "        [JsonPropertyName(\"section11_count\")]\n        public uint Section11Count { get; set; }\n"
+/// ));
+/// ```
+pub fn csharp_classes(registry: &Registry, namespace: &str) -> String {
+    let mut out = String::new();
+    write_csharp(&mut out, registry, namespace).expect("writing to a String cannot fail");
+    out
+}
+
+/// Python dataclasses mirroring the registry. A JSON object from the output can be passed to the
+/// class as keyword arguments, e.g. `Section9Entry(**obj)`.
+///
+/// # Arguments
+/// * `registry` - Formats from [`registry`]
+///
+/// # Example
+/// ```rust
+/// use fxr_binary_reader::fxr::formats::{python_classes, registry};
+///
+/// let source = python_classes(&registry().unwrap());
+/// assert!(source.contains("@dataclass\nclass Section9Entry:\n    unk00: int\n"));
+/// assert!(source.contains("class Header:\n    magic: str\n"));
+/// ```
+pub fn python_classes(registry: &Registry) -> String {
+    let mut out = String::new();
+    write_python(&mut out, registry).expect("writing to a String cannot fail");
+    out
+}
+
+fn write_csharp(out: &mut impl Write, registry: &Registry, namespace: &str) -> std::fmt::Result {
+    writeln!(
+        out,
+        "// Generated by `fxr types csharp` from the reader's section structs."
+    )?;
+    writeln!(out, "using System.Collections.Generic;")?;
+    writeln!(out, "using System.Text.Json.Serialization;")?;
+    writeln!(out)?;
+    writeln!(out, "namespace {}", namespace)?;
+    writeln!(out, "{{")?;
+    for (i, (name, container)) in registry.iter().enumerate() {
+        let ContainerFormat::Struct(fields) = container else {
+            continue;
+        };
+        if i > 0 {
+            writeln!(out)?;
+        }
+        writeln!(out, "    public sealed class {}", name)?;
+        writeln!(out, "    {{")?;
+        for field in fields {
+            let ty = csharp_type(&field.value);
+            let default = match ty.as_str() {
+                "string" => " = \"\";",
+                _ if ty.ends_with('>') || registry.contains_key(&ty) => " = new();",
+                _ => "",
+            };
+            writeln!(out, "        [JsonPropertyName(\"{}\")]", field.name)?;
+            writeln!(
+                out,
+                "        public {} {} {{ get; set; }}{}",
+                ty,
+                pascal_case(&field.name),
+                default
+            )?;
+        }
+        writeln!(out, "    }}")?;
+    }
+    writeln!(out, "}}")
+}
+
+fn write_python(out: &mut impl Write, registry: &Registry) -> std::fmt::Result {
+    writeln!(
+        out,
+        "# Generated by `fxr types python` from the reader's section structs."
+    )?;
+    writeln!(out, "from __future__ import annotations")?;
+    writeln!(out)?;
+    writeln!(out, "from dataclasses import dataclass")?;
+    writeln!(out, "from typing import Any, Optional")?;
+    for (name, container) in registry {
+        let ContainerFormat::Struct(fields) = container else {
+            continue;
+        };
+        writeln!(out)?;
+        writeln!(out)?;
+        writeln!(out, "@dataclass")?;
+        writeln!(out, "class {}:", name)?;
+        for field in fields {
+            writeln!(out, "    {}: {}", field.name, python_type(&field.value))?;
+        }
+    }
+    Ok(())
+}
+
+fn csharp_type(format: &Format) -> String {
+    match format {
+        Format::TypeName(name) => name.clone(),
+        Format::Bool => "bool".into(),
+        Format::I8 => "sbyte".into(),
+        Format::I16 => "short".into(),
+        Format::I32 => "int".into(),
+        Format::I64 => "long".into(),
+        Format::U8 => "byte".into(),
+        Format::U16 => "ushort".into(),
+        Format::U32 => "uint".into(),
+        Format::U64 => "ulong".into(),
+        Format::F32 => "float".into(),
+        Format::F64 => "double".into(),
+        Format::Char => "char".into(),
+        Format::Str => "string".into(),
+        Format::Bytes => "byte[]".into(),
+        Format::Option(inner) => format!("{}?", csharp_type(inner)),
+        Format::Seq(inner) => format!("List<{}>", csharp_type(inner)),
+        _ => "object".into(),
+    }
+}
+
+fn python_type(format: &Format) -> String {
+    match format {
+        Format::TypeName(name) => name.clone(),
+        Format::Bool => "bool".into(),
+        Format::I8
+        | Format::I16
+        | Format::I32
+        | Format::I64
+        | Format::I128
+        | Format::U8
+        | Format::U16
+        | Format::U32
+        | Format::U64
+        | Format::U128 => "int".into(),
+        Format::F32 | Format::F64 => "float".into(),
+        Format::Char | Format::Str => "str".into(),
+        Format::Bytes => "bytes".into(),
+        Format::Option(inner) => format!("Optional[{}]", python_type(inner)),
+        Format::Seq(inner) => format!("list[{}]", python_type(inner)),
+        _ => "Any".into(),
+    }
+}
+
+/// `section11_count` -> `Section11Count`
+fn pascal_case(name: &str) -> String {
+    name.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
@@ -5,9 +5,12 @@ use super::{
     util::{ParseError, parse_section_slice},
 };
 use crate::fxr::{
-    Header, parse_section_1_tree::parse_section1_tree, parse_section_4_tree::parse_section4_tree,
+    Header,
+    parse_section_1_tree::parse_section1_tree,
+    parse_section_4_tree::{parse_section4_tree, parse_section4_tree_with},
 };
-use std::error::Error;
+use serde::{Serialize, Serializer, ser::SerializeStruct};
+use std::{error::Error, ops::Deref};
 use validator::Validate;
 use zerocopy::Ref;
 
@@ -20,6 +23,19 @@ pub struct ParsedFXR<'a> {
     pub section14_entries: Option<Ref<&'a [u8], [Section14Entry]>>,
 }
 
+impl Serialize for ParsedFXR<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("ParsedFXR", 6)?;
+        state.serialize_field("header", self.header.deref())?;
+        state.serialize_field("section1_tree", &self.section1_tree)?;
+        state.serialize_field("section4_tree", &self.section4_tree)?;
+        state.serialize_field("section12_entries", &self.section12_entries.as_deref())?;
+        state.serialize_field("section13_entries", &self.section13_entries.as_deref())?;
+        state.serialize_field("section14_entries", &self.section14_entries.as_deref())?;
+        state.end()
+    }
+}
+
 impl Validate for ParsedFXR<'_> {
     fn validate(&self) -> Result<(), validator::ValidationErrors> {
         self.header.validate()?;
@@ -70,16 +86,40 @@ impl Validate for ParsedFXR<'_> {
 /// }
 /// ```
 pub fn parse_fxr<'a>(fxr_file_bytes: &'a [u8]) -> Result<ParsedFXR<'a>, Box<dyn Error>> {
-    let header_size = std::mem::size_of::<Header>();
+    parse_fxr_with(fxr_file_bytes, true)
+}
 
-    let header_ref =
-        Ref::<_, Header>::from_bytes(&fxr_file_bytes[..header_size]).map_err(|_| {
-            ParseError::InvalidHeader(Box::new(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                "Invalid Header",
-            )))
-        })?;
-    header_ref.validate()?;
+/// Parses an FXR file without running any `#[validate]` rules, for tools that study the values
+/// files actually hold, such as `fxr census`. Files whose structure can't be read still fail.
+///
+/// # Example
+/// ```rust
+/// use fxr_binary_reader::fxr::fxr_parser_with_sections::{parse_fxr, parse_fxr_unchecked};
+/// use validator::Validate;
+///
+/// let mut data = vec![0u8; 0xA0];
+/// data[0..4].copy_from_slice(b"FXR\0");
+/// data[6..8].copy_from_slice(&9u16.to_le_bytes()); // version, outside 4..=5
+/// data[8..12].copy_from_slice(&1u32.to_le_bytes()); // unk08
+/// data[0x68..0x6C].copy_from_slice(&1u32.to_le_bytes()); // unk68
+/// assert!(parse_fxr(&data).is_err());
+///
+/// let fxr = parse_fxr_unchecked(&data).unwrap();
+/// assert_eq!(fxr.header.version, 9);
+/// assert!(fxr.validate().is_err());
+/// ```
+pub fn parse_fxr_unchecked<'a>(fxr_file_bytes: &'a [u8]) -> Result<ParsedFXR<'a>, Box<dyn Error>> {
+    parse_fxr_with(fxr_file_bytes, false)
+}
+
+fn parse_fxr_with<'a>(
+    fxr_file_bytes: &'a [u8],
+    validate: bool,
+) -> Result<ParsedFXR<'a>, Box<dyn Error>> {
+    let header_ref = read_header(fxr_file_bytes)?;
+    if validate {
+        header_ref.validate()?;
+    }
 
     let section1_tree = if header_ref.section1_count > 0 {
         Some(parse_section1_tree(
@@ -91,9 +131,10 @@ pub fn parse_fxr<'a>(fxr_file_bytes: &'a [u8]) -> Result<ParsedFXR<'a>, Box<dyn
     };
 
     let section4_tree = if header_ref.section4_count > 0 {
-        Some(parse_section4_tree(
+        Some(parse_section4_tree_with(
             fxr_file_bytes,
             header_ref.section4_offset,
+            validate,
         )?)
     } else {
         None
@@ -141,3 +182,132 @@ pub fn parse_fxr<'a>(fxr_file_bytes: &'a [u8]) -> Result<ParsedFXR<'a>, Box<dyn
         section14_entries,
     })
 }
+
+/// Parses as much of an FXR file as possible, for inspecting files `parse_fxr` rejects.
+///
+/// Header validation failures are reported instead of returned, and a section that fails to parse
+/// (including the Section7 validation done while parsing Section4) is left out.
+/// # Returns
+/// The parsed file and one message per problem, e.g. `Section4: Struct given offset of ...`. Only
+/// a file too short to hold a header is an error.
+/// # Example
+/// ```rust
+/// use fxr_binary_reader::fxr::fxr_parser_with_sections::{parse_fxr, parse_fxr_lenient};
+///
+/// let mut data = vec![0u8; 0xA0];
+/// data[0..4].copy_from_slice(b"FXR\0");
+/// data[6..8].copy_from_slice(&5u16.to_le_bytes()); // version
+/// data[8..12].copy_from_slice(&1u32.to_le_bytes()); // unk08
+/// data[0x10..0x14].copy_from_slice(&0x90u32.to_le_bytes()); // section1_offset
+/// data[0x14..0x18].copy_from_slice(&1u32.to_le_bytes()); // section1_count
+/// data[0x68..0x6C].copy_from_slice(&1u32.to_le_bytes()); // unk68
+/// // Section4 points past the end of the file
+/// data[0x28..0x2C].copy_from_slice(&0x1000u32.to_le_bytes()); // section4_offset
+/// data[0x2C..0x30].copy_from_slice(&1u32.to_le_bytes()); // section4_count
+/// assert!(parse_fxr(&data).is_err());
+///
+/// let (fxr, skipped) = parse_fxr_lenient(&data).unwrap();
+/// assert!(fxr.section1_tree.is_some());
+/// assert!(fxr.section4_tree.is_none());
+/// assert_eq!(skipped.len(), 1);
+/// assert!(skipped[0].starts_with("Section4: "));
+/// ```
+pub fn parse_fxr_lenient<'a>(
+    fxr_file_bytes: &'a [u8],
+) -> Result<(ParsedFXR<'a>, Vec<String>), Box<dyn Error>> {
+    fn keep<T, E: std::fmt::Display>(
+        label: &str,
+        result: Result<T, E>,
+        skipped: &mut Vec<String>,
+    ) -> Option<T> {
+        result
+            .map_err(|err| skipped.push(format!("{}: {}", label, err)))
+            .ok()
+    }
+
+    let header_ref = read_header(fxr_file_bytes)?;
+    let mut skipped = Vec::new();
+    keep("Header", header_ref.validate(), &mut skipped);
+
+    let section1_tree = (header_ref.section1_count > 0)
+        .then(|| {
+            keep(
+                "Section1",
+                parse_section1_tree(fxr_file_bytes, header_ref.section1_offset),
+                &mut skipped,
+            )
+        })
+        .flatten();
+    let section4_tree = (header_ref.section4_count > 0)
+        .then(|| {
+            keep(
+                "Section4",
+                parse_section4_tree(fxr_file_bytes, header_ref.section4_offset),
+                &mut skipped,
+            )
+        })
+        .flatten();
+    let section12_entries = (header_ref.section12_count > 0)
+        .then(|| {
+            let entries = parse_section_slice::<Section12Entry>(
+                fxr_file_bytes,
+                header_ref.section12_offset,
+                header_ref.section12_count,
+                "Section12",
+            );
+            keep("Section12", entries, &mut skipped)
+        })
+        .flatten();
+    let section13_entries = (header_ref.section13_count > 0)
+        .then(|| {
+            let entries = parse_section_slice::<Section13Entry>(
+                fxr_file_bytes,
+                header_ref.section13_offset,
+                header_ref.section13_count,
+                "Section13",
+            );
+            keep("Section13", entries, &mut skipped)
+        })
+        .flatten();
+    let section14_entries = (header_ref.section14_count > 0)
+        .then(|| {
+            let entries = parse_section_slice::<Section14Entry>(
+                fxr_file_bytes,
+                header_ref.section14_offset,
+                header_ref.section14_count,
+                "Section14",
+            );
+            keep("Section14", entries, &mut skipped)
+        })
+        .flatten();
+
+    Ok((
+        ParsedFXR {
+            header: header_ref,
+            section1_tree,
+            section4_tree,
+            section12_entries,
+            section13_entries,
+            section14_entries,
+        },
+        skipped,
+    ))
+}
+
+/// The header at the start of the file, without validating it
+fn read_header(fxr_file_bytes: &[u8]) -> Result<Ref<&[u8], Header>, ParseError> {
+    let header_size = std::mem::size_of::<Header>();
+
+    let header_bytes = fxr_file_bytes
+        .get(..header_size)
+        .ok_or(ParseError::BufferTooSmall {
+            expected: header_size,
+            actual: fxr_file_bytes.len(),
+        })?;
+    Ref::<_, Header>::from_bytes(header_bytes).map_err(|_| {
+        ParseError::InvalidHeader(Box::new(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "Invalid Header",
+        )))
+    })
+}
@@ -0,0 +1,89 @@
+use super::{
+    catalog::{section_at, sections},
+    walk::PathSegment,
+};
+use std::ops::Range;
+
+/// Name and byte range of one field within its struct
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldSpan {
+    pub name: &'static str,
+    /// Offset of the field from the start of the struct
+    pub offset: usize,
+    pub size: usize,
+}
+
+impl FieldSpan {
+    pub fn range(&self) -> Range<usize> {
+        self.offset..self.offset + self.size
+    }
+}
+
+/// Byte layout of a `#[repr(C)]` section struct, in declaration order.
+///
+/// The structs have no padding, so each field runs up to the start of the next one.
+///
+/// # Example
+/// ```rust
+/// use fxr_binary_reader::fxr::{Section8Entry, layout::FieldLayout};
+///
+/// let unk04 = Section8Entry::FIELDS[4];
+/// assert_eq!(unk04.name, "unk04");
+/// assert_eq!(unk04.range(), 4..8);
+/// ```
+pub trait FieldLayout {
+    const FIELDS: &'static [FieldSpan];
+}
+
+/// Builds the spans from field names and offsets, sizing each field up to the next one.
+/// Used by `#[derive(FxrSection)]`.
+#[doc(hidden)]
+pub const fn spans<const N: usize>(
+    names: [&'static str; N],
+    offsets: [usize; N],
+    struct_size: usize,
+) -> [FieldSpan; N] {
+    let mut spans = [FieldSpan {
+        name: "",
+        offset: 0,
+        size: 0,
+    }; N];
+    let mut i = 0;
+    while i < N {
+        let end = if i + 1 < N {
+            offsets[i + 1]
+        } else {
+            struct_size
+        };
+        spans[i] = FieldSpan {
+            name: names[i],
+            offset: offsets[i],
+            size: end - offsets[i],
+        };
+        i += 1;
+    }
+    spans
+}
+
+/// Struct name and layout for a path segment such as `Section6[0]`.
+///
+/// Sections that are a single container (`Section1`, `Section4`, `Section7`, ...) have no
+/// index and arrays have one; `Section4` and `Section4[i]` are different structs.
+pub fn struct_for(segment: PathSegment) -> Option<(&'static str, &'static [FieldSpan])> {
+    section_at(segment.section, segment.index.is_some()).map(|info| (info.name, info.fields))
+}
+
+/// Every struct type found in the parsed tree with its layout, in section order
+pub fn structs() -> impl Iterator<Item = (&'static str, &'static [FieldSpan])> {
+    sections()
+        .into_iter()
+        .filter(|info| info.section.is_some())
+        .map(|info| (info.name, info.fields))
+}
+
+/// Layout of the struct with the given name, e.g. `Section6Entry`
+pub fn struct_named(kind: &str) -> Option<&'static [FieldSpan]> {
+    structs()
+        .find(|(name, _)| *name == kind)
+        .map(|(_, layout)| layout)
+}
@@ -0,0 +1,432 @@
+use super::{
+    fxr_parser_with_sections::parse_fxr,
+    walk::{Node, SectionPath, walk},
+    writer::FxrWriter,
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt::{Display, Formatter},
+};
+
+/// One of the two edited versions being merged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Side {
+    Ours,
+    Theirs,
+}
+
+impl Side {
+    fn other(self) -> Side {
+        match self {
+            Side::Ours => Side::Theirs,
+            Side::Theirs => Side::Ours,
+        }
+    }
+}
+
+impl Display for Side {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Side::Ours => write!(f, "ours"),
+            Side::Theirs => write!(f, "theirs"),
+        }
+    }
+}
+
+/// A change that couldn't be merged automatically
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "conflict", rename_all = "snake_case")]
+pub enum Conflict {
+    /// Both sides changed the field to different values
+    Field {
+        path: SectionPath,
+        field: &'static str,
+        ours: Value,
+        theirs: Value,
+    },
+    /// `side` added a struct, but the other side changed the layout in a different way so there is
+    /// nowhere to put it
+    Added {
+        path: SectionPath,
+        kind: &'static str,
+        side: Side,
+    },
+    /// `side` removed a struct that the other side kept or modified
+    Removed {
+        path: SectionPath,
+        kind: &'static str,
+        side: Side,
+    },
+    /// Bytes that don't belong to any parsed struct changed in a way that can't be carried over
+    Unclaimed { offset: usize, len: usize },
+}
+
+impl Conflict {
+    /// Section path of the conflict. Unclaimed bytes have the empty root path.
+    pub fn path(&self) -> SectionPath {
+        match self {
+            Conflict::Field { path, .. }
+            | Conflict::Added { path, .. }
+            | Conflict::Removed { path, .. } => path.clone(),
+            Conflict::Unclaimed { .. } => SectionPath::default(),
+        }
+    }
+}
+
+impl Display for Conflict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Conflict::Field {
+                path,
+                field,
+                ours,
+                theirs,
+            } => write!(
+                f,
+                "both modified: {}.{} (ours {}, theirs {})",
+                path, field, ours, theirs
+            ),
+            Conflict::Added { path, kind, side } => write!(
+                f,
+                "added in {}, but {} changed the layout: {} ({})",
+                side,
+                side.other(),
+                path,
+                kind
+            ),
+            Conflict::Removed { path, kind, side } => write!(
+                f,
+                "removed in {}, kept in {}: {} ({})",
+                side,
+                side.other(),
+                path,
+                kind
+            ),
+            Conflict::Unclaimed { offset, len } => write!(
+                f,
+                "unclaimed bytes changed: 0x{:X}..0x{:X}",
+                offset,
+                offset + len
+            ),
+        }
+    }
+}
+
+/// Output of [`merge`]
+#[derive(Debug, Clone)]
+pub struct MergeResult {
+    /// The merged file. Where there are conflicts, the conflicting fields keep the value of
+    /// whichever side the result was built from.
+    pub bytes: Vec<u8>,
+    pub conflicts: Vec<Conflict>,
+}
+
+impl MergeResult {
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// Merges two edited versions of a file against their common ancestor.
+///
+/// Structs are matched by [`SectionPath`] and merged field by field: a field changed on one side
+/// takes that side's value, a field changed identically on both sides is kept, and a field changed
+/// differently on both sides is a [`Conflict::Field`].
+///
+/// Adding or removing structs moves data around, which the writer can't redo on its own. If only
+/// one side did that, the result is built from that side's bytes and the other side's field
+/// changes are written on top at the new offsets. If both sides changed the layout, the result is
+/// built from ours and every struct that only exists on one side is reported as a conflict.
+/// Changes to bytes outside any parsed struct are only merged when neither side changed the
+/// layout.
+///
+/// # Arguments
+/// - `base`: The common ancestor.
+/// - `ours`: Our version, the one the result is based on when the choice doesn't matter.
+/// - `theirs`: Their version.
+///
+/// # Returns
+/// - `Ok(MergeResult)`: The merged bytes along with any conflicts, ordered by section path.
+/// - `Err(Box<dyn Error>)`: If one of the inputs or the merged result fails to parse.
+///
+/// # Example
+/// ```rust
+/// use fxr_binary_reader::fxr::merge::merge;
+///
+/// let mut base = vec![0u8; 0xA0];
+/// base[0..4].copy_from_slice(b"FXR\0");
+/// base[6..8].copy_from_slice(&5u16.to_le_bytes()); // version
+/// base[8..12].copy_from_slice(&1u32.to_le_bytes()); // unk08
+/// base[0x10..0x14].copy_from_slice(&0x90u32.to_le_bytes()); // section1_offset
+/// base[0x14..0x18].copy_from_slice(&1u32.to_le_bytes()); // section1_count
+/// base[0x68..0x6C].copy_from_slice(&1u32.to_le_bytes()); // unk68
+///
+/// let mut ours = base.clone();
+/// ours[0x0C..0x10].copy_from_slice(&302421u32.to_le_bytes()); // ffx_id
+/// let mut theirs = base.clone();
+/// theirs[0x90..0x94].copy_from_slice(&7u32.to_le_bytes()); // Section1 unk00
+///
+/// let result = merge(&base, &ours, &theirs).unwrap();
+/// assert!(result.is_clean());
+/// assert_eq!(&result.bytes[0x0C..0x10], &302421u32.to_le_bytes());
+/// assert_eq!(&result.bytes[0x90..0x94], &7u32.to_le_bytes());
+///
+/// theirs[0x0C..0x10].copy_from_slice(&1u32.to_le_bytes());
+/// let result = merge(&base, &ours, &theirs).unwrap();
+/// assert_eq!(
+///     result.conflicts[0].to_string(),
+///     "both modified: Header.ffx_id (ours 302421, theirs 1)"
+/// );
+/// ```
+pub fn merge(base: &[u8], ours: &[u8], theirs: &[u8]) -> Result<MergeResult, Box<dyn Error>> {
+    let base_fxr = parse_fxr(base)?;
+    let ours_fxr = parse_fxr(ours)?;
+    let theirs_fxr = parse_fxr(theirs)?;
+    let base_nodes = by_path(walk(&base_fxr));
+    let ours_nodes = by_path(walk(&ours_fxr));
+    let theirs_nodes = by_path(walk(&theirs_fxr));
+
+    let ours_moved = layout_changed(base, &base_nodes, ours, &ours_nodes);
+    let theirs_moved = layout_changed(base, &base_nodes, theirs, &theirs_nodes);
+
+    // Build on whichever side moved things around, so only field values need carrying over
+    let (carrier, other) = if theirs_moved && !ours_moved {
+        (
+            Version::new(Side::Theirs, theirs, theirs_nodes),
+            Version::new(Side::Ours, ours, ours_nodes),
+        )
+    } else {
+        (
+            Version::new(Side::Ours, ours, ours_nodes),
+            Version::new(Side::Theirs, theirs, theirs_nodes),
+        )
+    };
+
+    let mut merger = Merger {
+        writer: FxrWriter::from_bytes(carrier.bytes.to_vec()),
+        conflicts: Vec::new(),
+    };
+    for (path, node) in &other.nodes {
+        match (base_nodes.get(path), carrier.nodes.get(path)) {
+            (base_node, Some(carrier_node)) => {
+                merger.fields(path, base_node, carrier.side, carrier_node, node)
+            }
+            (None, None) => merger.conflicts.push(Conflict::Added {
+                path: path.clone(),
+                kind: node.section.kind(),
+                side: other.side,
+            }),
+            (Some(base_node), None) => {
+                if base_node.section.as_bytes() != node.section.as_bytes() {
+                    merger.conflicts.push(Conflict::Removed {
+                        path: path.clone(),
+                        kind: node.section.kind(),
+                        side: carrier.side,
+                    });
+                }
+            }
+        }
+    }
+    for (path, base_node) in &base_nodes {
+        if carrier.nodes.contains_key(path) && !other.nodes.contains_key(path) {
+            merger.conflicts.push(Conflict::Removed {
+                path: path.clone(),
+                kind: base_node.section.kind(),
+                side: other.side,
+            });
+        }
+    }
+    let (carrier_moved, other_moved) = match carrier.side {
+        Side::Ours => (ours_moved, theirs_moved),
+        Side::Theirs => (theirs_moved, ours_moved),
+    };
+    if !other_moved {
+        merger.unclaimed(
+            base,
+            &base_nodes,
+            carrier.bytes,
+            other.bytes,
+            !carrier_moved,
+        );
+    }
+
+    let Merger {
+        writer,
+        mut conflicts,
+    } = merger;
+    let bytes = writer.into_bytes();
+    parse_fxr(&bytes)?;
+
+    conflicts.sort_by_key(Conflict::path);
+    Ok(MergeResult {
+        bytes,
+        conflicts: remove_nested_conflicts(conflicts),
+    })
+}
+
+struct Version<'a, 'n> {
+    side: Side,
+    bytes: &'a [u8],
+    nodes: BTreeMap<SectionPath, Node<'n>>,
+}
+
+impl<'a, 'n> Version<'a, 'n> {
+    fn new(side: Side, bytes: &'a [u8], nodes: BTreeMap<SectionPath, Node<'n>>) -> Self {
+        Self { side, bytes, nodes }
+    }
+}
+
+struct Merger {
+    writer: FxrWriter,
+    conflicts: Vec<Conflict>,
+}
+
+impl Merger {
+    /// Carries the other side's field changes over to the carrier's copy of the struct
+    fn fields(
+        &mut self,
+        path: &SectionPath,
+        base: Option<&Node>,
+        carrier_side: Side,
+        carrier_node: &Node,
+        other_node: &Node,
+    ) {
+        let base_bytes = base.map(|node| node.section.as_bytes());
+        let carrier_bytes = carrier_node.section.as_bytes();
+        let other_bytes = other_node.section.as_bytes();
+        if carrier_bytes == other_bytes {
+            return;
+        }
+
+        for span in carrier_node.section.layout() {
+            let base_value = base_bytes.map(|bytes| &bytes[span.range()]);
+            let carrier_value = &carrier_bytes[span.range()];
+            let other_value = &other_bytes[span.range()];
+            if other_value == carrier_value || Some(other_value) == base_value {
+                continue;
+            }
+            if Some(carrier_value) == base_value {
+                self.writer
+                    .write_bytes(carrier_node.offset + span.offset, other_value);
+                continue;
+            }
+
+            let carrier_field = field_value(carrier_node, span.name);
+            let other_field = field_value(other_node, span.name);
+            let (ours, theirs) = match carrier_side {
+                Side::Ours => (carrier_field, other_field),
+                Side::Theirs => (other_field, carrier_field),
+            };
+            self.conflicts.push(Conflict::Field {
+                path: path.clone(),
+                field: span.name,
+                ours,
+                theirs,
+            });
+        }
+    }
+
+    /// Merges bytes that aren't part of any struct. Only called when `other` has the base layout;
+    /// if the carrier doesn't, the other side's changes have nowhere to go.
+    fn unclaimed(
+        &mut self,
+        base: &[u8],
+        base_nodes: &BTreeMap<SectionPath, Node>,
+        carrier: &[u8],
+        other: &[u8],
+        same_layout: bool,
+    ) {
+        let mut claimed = vec![false; base.len()];
+        for node in base_nodes.values() {
+            let end = node.offset + node.section.as_bytes().len();
+            claimed[node.offset..end].fill(true);
+        }
+
+        let mut conflict: Option<(usize, usize)> = None;
+        for (i, is_claimed) in claimed.into_iter().enumerate() {
+            if is_claimed || other[i] == base[i] {
+                continue;
+            }
+            if same_layout {
+                if carrier[i] == other[i] {
+                    continue;
+                }
+                if carrier[i] == base[i] {
+                    self.writer.write_bytes(i, &other[i..=i]);
+                    continue;
+                }
+            }
+            conflict = match conflict {
+                Some((start, end)) if end == i => Some((start, i + 1)),
+                Some((start, end)) => {
+                    self.push_unclaimed(start, end);
+                    Some((i, i + 1))
+                }
+                None => Some((i, i + 1)),
+            };
+        }
+        if let Some((start, end)) = conflict {
+            self.push_unclaimed(start, end);
+        }
+    }
+
+    fn push_unclaimed(&mut self, start: usize, end: usize) {
+        self.conflicts.push(Conflict::Unclaimed {
+            offset: start,
+            len: end - start,
+        });
+    }
+}
+
+fn by_path(nodes: Vec<Node>) -> BTreeMap<SectionPath, Node> {
+    nodes
+        .into_iter()
+        .map(|node| (node.path.clone(), node))
+        .collect()
+}
+
+/// True if structs were added, removed or moved, or the file changed size
+fn layout_changed(
+    base: &[u8],
+    base_nodes: &BTreeMap<SectionPath, Node>,
+    side: &[u8],
+    side_nodes: &BTreeMap<SectionPath, Node>,
+) -> bool {
+    base.len() != side.len()
+        || !base_nodes
+            .iter()
+            .map(|(path, node)| (path, node.offset))
+            .eq(side_nodes.iter().map(|(path, node)| (path, node.offset)))
+}
+
+fn field_value(node: &Node, field: &str) -> Value {
+    node.section
+        .fields()
+        .into_iter()
+        .find(|(name, _)| name == field)
+        .map(|(_, value)| value)
+        .unwrap_or(Value::Null)
+}
+
+/// Drops struct conflicts inside a subtree that is already reported as added or removed
+fn remove_nested_conflicts(conflicts: Vec<Conflict>) -> Vec<Conflict> {
+    let mut kept: Vec<Conflict> = Vec::with_capacity(conflicts.len());
+    let mut subtree: Option<SectionPath> = None;
+    for conflict in conflicts {
+        match &conflict {
+            Conflict::Added { path, .. } | Conflict::Removed { path, .. } => {
+                if subtree.as_ref().is_some_and(|root| path.starts_with(root)) {
+                    continue;
+                }
+                subtree = Some(path.clone());
+            }
+            Conflict::Field { .. } | Conflict::Unclaimed { .. } => {}
+        }
+        kept.push(conflict);
+    }
+    kept
+}
@@ -1,3 +1,4 @@
+use fxr_derive::FxrSection;
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 use thiserror::Error;
@@ -5,11 +6,28 @@ use validator::{Validate, ValidationError};
 use zerocopy::IntoBytes;
 use zerocopy_derive::{FromBytes, Immutable, IntoBytes, KnownLayout};
 
+pub mod archive;
+pub mod asm;
+pub mod catalog;
+pub mod census;
+pub mod diff;
+pub mod formats;
 pub mod fxr_parser_with_sections;
+pub mod layout;
+pub mod merge;
 pub mod parse_section_1_tree;
 pub mod parse_section_4_tree;
 pub mod parse_section_6_nested;
+pub mod patch;
+pub mod query;
+pub mod records;
+pub mod reflect;
+pub mod rules;
+pub mod templates;
+pub mod textconv;
 pub mod util;
+pub mod walk;
+pub mod writer;
 
 mod hex_formatted_bytes {
     use serde::{self, Deserialize, Deserializer, Serializer};
@@ -45,25 +63,11 @@ mod string_formatted_bytes {
     }
 }
 
+/// A struct holding a single `u32` payload, implemented by `#[derive(FxrSection)]` for the
+/// field marked `#[fxr(data)]`
 pub trait U32Field {
     fn data(&self) -> u32;
 }
-
-impl U32Field for crate::fxr::Section12Entry {
-    fn data(&self) -> u32 {
-        self.data
-    }
-}
-impl U32Field for crate::fxr::Section13Entry {
-    fn data(&self) -> u32 {
-        self.data
-    }
-}
-impl U32Field for crate::fxr::Section14Entry {
-    fn data(&self) -> u32 {
-        self.data
-    }
-}
 fn validate_fxr_type_magic_bytes(magic: u32) -> Result<(), ValidationError> {
     let mut err = ValidationError::new("Header Magic");
     if magic != u32::from_le_bytes([b'F', b'X', b'R', 0]) {
@@ -81,19 +85,27 @@ fn validate_fxr_type_magic_bytes(magic: u32) -> Result<(), ValidationError> {
 }
 
 #[repr(C)]
-#[derive(Error, Debug, FromBytes, IntoBytes, Immutable, KnownLayout, Serialize, Validate)]
+#[derive(
+    Error, Debug, FromBytes, IntoBytes, Immutable, KnownLayout, Serialize, Validate, FxrSection,
+)]
 #[validate(schema(function = "validate_conditional_fields", skip_on_field_errors = false))]
+#[fxr(validate)]
+#[fxr(section = 0)]
 pub struct Header {
     #[validate(custom(function = "validate_fxr_type_magic_bytes"))]
     #[serde(with = "string_formatted_bytes")]
+    #[fxr(display = "hex")]
     pub magic: u32,
     unk04: u16,
     #[validate(range(min = 4, max = 5))]
+    #[fxr(display)]
     pub version: u16,
     #[validate(range(min = 1, max = 1))]
     unk08: u32,
+    #[fxr(display)]
     pub ffx_id: u32,
     #[serde(with = "hex_formatted_bytes")]
+    #[fxr(points_to = "Section1Container", count = "section1_count", single)]
     pub section1_offset: u32,
     #[validate(range(min = 1, max = 1))]
     pub section1_count: u32,
@@ -104,6 +116,7 @@ pub struct Header {
     pub section3_offset: u32,
     pub section3_count: u32,
     #[serde(with = "hex_formatted_bytes")]
+    #[fxr(points_to = "Section4Container", count = "section4_count", single)]
     pub section4_offset: u32,
     pub section4_count: u32,
     #[serde(with = "hex_formatted_bytes")]
@@ -133,12 +146,15 @@ pub struct Header {
     unk70: u32,
 
     #[serde(with = "hex_formatted_bytes")]
+    #[fxr(points_to = "Section12Entry", count = "section12_count")]
     pub section12_offset: u32,
     pub section12_count: u32,
     #[serde(with = "hex_formatted_bytes")]
+    #[fxr(points_to = "Section13Entry", count = "section13_count")]
     pub section13_offset: u32,
     pub section13_count: u32,
     #[serde(with = "hex_formatted_bytes")]
+    #[fxr(points_to = "Section14Entry", count = "section14_count")]
     pub section14_offset: u32,
     pub section14_count: u32,
     unk88: u32,
@@ -221,8 +237,19 @@ fn validate_conditional_fields(header: &&Header) -> Result<(), ValidationError>
 
 #[repr(C)]
 #[derive(
-    Error, Validate, Debug, FromBytes, IntoBytes, Immutable, KnownLayout, Serialize, Deserialize,
+    Error,
+    Validate,
+    Debug,
+    FromBytes,
+    IntoBytes,
+    Immutable,
+    KnownLayout,
+    Serialize,
+    Deserialize,
+    FxrSection,
 )]
+#[fxr(validate)]
+#[fxr(section = 4)]
 pub struct Section4Container {
     unk00: u16,
     unk02: u8,
@@ -231,15 +258,24 @@ pub struct Section4Container {
     unk04: u32,
     pub section5_count: u32,
     pub section6_count: u32,
+    #[fxr(display)]
     pub section4_count: u32,
     #[validate(range(min = 0, max = 0))]
     unk14: u32,
+    #[serde(with = "hex_formatted_bytes")]
+    #[fxr(display = "hex")]
+    #[fxr(points_to = "Section5Entry", count = "section5_count")]
     pub section5_offset: u32,
     #[validate(range(min = 0, max = 0))]
     unk1c: u32,
+    #[serde(with = "hex_formatted_bytes")]
+    #[fxr(display = "hex")]
+    #[fxr(points_to = "Section6Entry", count = "section6_count")]
     pub section6_offset: u32,
     #[validate(range(min = 0, max = 0))]
     unk24: u32,
+    #[serde(with = "hex_formatted_bytes")]
+    #[fxr(points_to = "Section4Entry", count = "section4_count")]
     pub section4_offset: u32,
     #[validate(range(min = 0, max = 0))]
     unk2c: u32,
@@ -247,26 +283,61 @@ pub struct Section4Container {
 
 #[repr(C)]
 #[derive(
-    Error, Validate, Debug, FromBytes, IntoBytes, Immutable, KnownLayout, Serialize, Deserialize,
+    Error,
+    Validate,
+    Debug,
+    FromBytes,
+    IntoBytes,
+    Immutable,
+    KnownLayout,
+    Serialize,
+    Deserialize,
+    FxrSection,
 )]
+#[fxr(validate)]
+#[fxr(section = 4, array)]
 pub struct Section4Entry {
     // Placeholder structure
+    #[fxr(display = "hex")]
     unk00: u32,
 }
 
 #[repr(C)]
 #[derive(
-    Error, Validate, Debug, FromBytes, IntoBytes, Immutable, KnownLayout, Serialize, Deserialize,
+    Error,
+    Validate,
+    Debug,
+    FromBytes,
+    IntoBytes,
+    Immutable,
+    KnownLayout,
+    Serialize,
+    Deserialize,
+    FxrSection,
 )]
+#[fxr(validate)]
+#[fxr(section = 5, array)]
 pub struct Section5Entry {
     // Placeholder structure
+    #[fxr(display = "hex")]
     unk00: u32,
 }
 
 #[repr(C)]
 #[derive(
-    Error, Validate, Debug, FromBytes, IntoBytes, KnownLayout, Immutable, Serialize, Deserialize,
+    Error,
+    Validate,
+    Debug,
+    FromBytes,
+    IntoBytes,
+    KnownLayout,
+    Immutable,
+    Serialize,
+    Deserialize,
+    FxrSection,
 )]
+#[fxr(validate)]
+#[fxr(section = 6, array)]
 pub struct Section6Entry {
     unk00: u16,
     unk02: u8,
@@ -280,14 +351,20 @@ pub struct Section6Entry {
     unk18: u32,
     pub section7_count2: u32,
     #[serde(with = "hex_formatted_bytes")]
+    #[fxr(display = "hex")]
+    #[fxr(points_to = "Section11Entry", count = "section11_count1")]
     pub section11_offset: u32,
     #[validate(range(min = 0, max = 0))]
     unk24: u32,
     #[serde(with = "hex_formatted_bytes")]
+    #[fxr(display = "hex")]
+    #[fxr(points_to = "Section10Container", count = "section10_count", single)]
     pub section10_offset: u32,
     #[validate(range(min = 0, max = 0))]
     unk2c: u32,
     #[serde(with = "hex_formatted_bytes")]
+    #[fxr(display = "hex")]
+    #[fxr(points_to = "Section7Container", count = "section7_count1", single)]
     pub section7_offset: u32,
     #[validate(range(min = 0, max = 0))]
     unk34: u32,
@@ -299,12 +376,27 @@ pub struct Section6Entry {
 
 #[repr(C)]
 #[derive(
-    Error, Debug, FromBytes, IntoBytes, Immutable, KnownLayout, Serialize, Deserialize, Validate,
+    Error,
+    Debug,
+    FromBytes,
+    IntoBytes,
+    Immutable,
+    KnownLayout,
+    Serialize,
+    Deserialize,
+    Validate,
+    FxrSection,
 )]
+#[fxr(validate)]
+#[fxr(section = 1)]
 pub struct Section1Container {
     #[validate(range(min = 0, max = 0))]
     unk00: u32,
+    #[fxr(display)]
     pub section2_count: u32,
+    #[serde(with = "hex_formatted_bytes")]
+    #[fxr(display = "hex")]
+    #[fxr(points_to = "Section2Container", count = "section2_count", single)]
     pub section2_offset: u32,
     #[validate(range(min = 0, max = 0))]
     unk0c: u32,
@@ -312,13 +404,27 @@ pub struct Section1Container {
 
 #[repr(C)]
 #[derive(
-    Error, Debug, FromBytes, IntoBytes, Immutable, KnownLayout, Serialize, Deserialize, Validate,
+    Error,
+    Debug,
+    FromBytes,
+    IntoBytes,
+    Immutable,
+    KnownLayout,
+    Serialize,
+    Deserialize,
+    Validate,
+    FxrSection,
 )]
+#[fxr(validate)]
+#[fxr(section = 2)]
 pub struct Section2Container {
     #[validate(range(min = 0, max = 0))]
     unk00: u32,
+    #[fxr(display)]
     pub section3_count: u32,
     #[serde(with = "hex_formatted_bytes")]
+    #[fxr(display = "hex")]
+    #[fxr(points_to = "Section3Entry", count = "section3_count")]
     pub section3_offset: u32,
     #[validate(range(min = 0, max = 0))]
     unk0c: u32,
@@ -326,8 +432,19 @@ pub struct Section2Container {
 
 #[repr(C)]
 #[derive(
-    Error, Debug, FromBytes, IntoBytes, Immutable, KnownLayout, Serialize, Deserialize, Validate,
+    Error,
+    Debug,
+    FromBytes,
+    IntoBytes,
+    Immutable,
+    KnownLayout,
+    Serialize,
+    Deserialize,
+    Validate,
+    FxrSection,
 )]
+#[fxr(validate)]
+#[fxr(section = 3, array)]
 pub struct Section3Entry {
     #[validate(range(min = 11, max = 10))]
     unk00: u16,
@@ -344,9 +461,12 @@ pub struct Section3Entry {
     #[validate(range(min = 0, max = 0))]
     unk14: u32,
     #[validate(range(min = 1, max = 1))]
-    unk18: u32,
+    pub section11_count1: u32,
     #[validate(range(min = 0, max = 0))]
     unk1c: u32,
+    #[serde(with = "hex_formatted_bytes")]
+    #[fxr(display = "hex")]
+    #[fxr(points_to = "Section11Entry", count = "section11_count1")]
     pub section11_offset1: u32,
     #[validate(range(min = 0, max = 0))]
     unk24: u32,
@@ -363,9 +483,12 @@ pub struct Section3Entry {
     #[validate(range(min = 0, max = 0))]
     unk3c: u32,
     #[validate(range(min = 0, max = 1))]
-    unk40: u32,
+    pub section11_count2: u32,
     #[validate(range(min = 0, max = 0))]
     unk44: u32,
+    #[serde(with = "hex_formatted_bytes")]
+    #[fxr(display = "hex")]
+    #[fxr(points_to = "Section11Entry", count = "section11_count2")]
     pub section11_offset2: u32,
     #[validate(range(min = 0, max = 0))]
     unk4c: u32,
@@ -391,7 +514,10 @@ pub struct Section3Entry {
     Serialize,
     Deserialize,
     Default,
+    FxrSection,
 )]
+#[fxr(validate)]
+#[fxr(section = 7)]
 pub struct Section7Container {
     #[serde(with = "hex_formatted_bytes")]
     unk00: u32,
@@ -401,10 +527,14 @@ pub struct Section7Container {
     #[validate(range(min = 0, max = 0))]
     unk0c: u32,
     #[serde(with = "hex_formatted_bytes")]
+    #[fxr(display = "hex")]
+    #[fxr(points_to = "Section11Entry", count = "section11_count")]
     pub section11_offset: u32,
     #[validate(range(min = 0, max = 0))]
     unk14: u32,
     #[serde(with = "hex_formatted_bytes")]
+    #[fxr(display = "hex")]
+    #[fxr(points_to = "Section8Entry", count = "section8_count")]
     pub section8_offset: u32,
     #[validate(range(min = 0, max = 0))]
     unk1c: u32,
@@ -416,8 +546,18 @@ pub struct Section7Container {
 
 #[repr(C)]
 #[derive(
-    Error, Validate, Debug, FromBytes, IntoBytes, KnownLayout, Immutable, Serialize, Deserialize,
+    Error,
+    Validate,
+    Debug,
+    FromBytes,
+    IntoBytes,
+    KnownLayout,
+    Immutable,
+    Serialize,
+    Deserialize,
+    FxrSection,
 )]
+#[fxr(validate)]
 pub struct Section8Container {
     unk00: u8,
     unk01: u8,
@@ -427,10 +567,12 @@ pub struct Section8Container {
     pub section11_count: u32,
     pub section9_count: u32,
     #[serde(with = "hex_formatted_bytes")]
+    #[fxr(display = "hex")]
     pub section11_offset: u32,
     #[validate(range(min = 0, max = 0))]
     unk14: u32,
     #[serde(with = "hex_formatted_bytes")]
+    #[fxr(display = "hex")]
     pub section9_offset: u32,
     #[validate(range(min = 0, max = 0))]
     unk1c: u32,
@@ -438,8 +580,18 @@ pub struct Section8Container {
 
 #[repr(C)]
 #[derive(
-    Error, Validate, Debug, FromBytes, IntoBytes, KnownLayout, Immutable, Serialize, Deserialize,
+    Error,
+    Validate,
+    Debug,
+    FromBytes,
+    IntoBytes,
+    KnownLayout,
+    Immutable,
+    Serialize,
+    Deserialize,
+    FxrSection,
 )]
+#[fxr(validate)]
 pub struct Section9Container {
     unk00: u32,
     unk04: u32,
@@ -447,6 +599,7 @@ pub struct Section9Container {
     #[validate(range(min = 0, max = 0))]
     unk0c: u32,
     #[serde(with = "hex_formatted_bytes")]
+    #[fxr(display = "hex")]
     pub section11_offset: u32,
     #[validate(range(min = 0, max = 0))]
     unk14: u32,
@@ -454,13 +607,27 @@ pub struct Section9Container {
 
 #[repr(C)]
 #[derive(
-    Error, Validate, Debug, FromBytes, IntoBytes, KnownLayout, Immutable, Serialize, Deserialize,
+    Error,
+    Validate,
+    Debug,
+    FromBytes,
+    IntoBytes,
+    KnownLayout,
+    Immutable,
+    Serialize,
+    Deserialize,
+    FxrSection,
 )]
+#[fxr(validate)]
+#[fxr(section = 10)]
 pub struct Section10Container {
     #[serde(with = "hex_formatted_bytes")]
+    #[fxr(display = "hex")]
+    #[fxr(points_to = "Section11Entry", count = "section11_count")]
     pub section11_offset: u32,
     #[validate(range(min = 0, max = 0))]
     unk04: u32,
+    #[fxr(display)]
     pub section11_count: u32,
     #[validate(range(min = 0, max = 0))]
     unk0c: u32,
@@ -468,215 +635,154 @@ pub struct Section10Container {
 
 #[repr(C)]
 #[derive(
-    Error, Validate, Debug, FromBytes, IntoBytes, KnownLayout, Immutable, Serialize, Deserialize,
+    Error,
+    Validate,
+    Debug,
+    FromBytes,
+    IntoBytes,
+    KnownLayout,
+    Immutable,
+    Serialize,
+    Deserialize,
+    FxrSection,
 )]
+#[fxr(validate)]
+#[fxr(section = 12, array)]
 pub struct Section12Entry {
+    #[fxr(display = "hex", data)]
     data: u32, // Assuming each entry is 4 bytes
 }
 
 #[repr(C)]
 #[derive(
-    Error, Validate, Debug, FromBytes, IntoBytes, KnownLayout, Immutable, Serialize, Deserialize,
+    Error,
+    Validate,
+    Debug,
+    FromBytes,
+    IntoBytes,
+    KnownLayout,
+    Immutable,
+    Serialize,
+    Deserialize,
+    FxrSection,
 )]
+#[fxr(validate)]
+#[fxr(section = 13, array)]
 pub struct Section13Entry {
+    #[fxr(display = "hex", data)]
     data: u32,
 }
 
 #[repr(C)]
 #[derive(
-    Error, Validate, Debug, FromBytes, IntoBytes, KnownLayout, Immutable, Serialize, Deserialize,
+    Error,
+    Validate,
+    Debug,
+    FromBytes,
+    IntoBytes,
+    KnownLayout,
+    Immutable,
+    Serialize,
+    Deserialize,
+    FxrSection,
 )]
+#[fxr(validate)]
+#[fxr(section = 11, array)]
 pub struct Section11Entry {
+    #[fxr(display)]
     pub data: u32,
 }
 
+/// Best guess at what a Section11 slot holds. The format stores integers and `f32`s in the same
+/// array without type tags, so this is a heuristic rather than a decoding.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Section11Value {
+    Int(i32),
+    Float(f32),
+}
+
+impl Section11Entry {
+    /// Interprets the slot as a float when the bits form a normal `f32` of plausible magnitude,
+    /// and as a signed integer otherwise (so `0xFFFFFFFF` reads as `-1`).
+    pub fn value(&self) -> Section11Value {
+        let float = f32::from_bits(self.data);
+        if float.is_normal() && (1e-6..=1e9).contains(&float.abs()) {
+            Section11Value::Float(float)
+        } else {
+            Section11Value::Int(self.data as i32)
+        }
+    }
+}
+
+impl Display for Section11Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Section11Value::Int(value) => write!(f, "{}", value),
+            Section11Value::Float(value) => write!(f, "{:?}f", value),
+        }
+    }
+}
+
 #[repr(C)]
 #[derive(
-    Error, Validate, Debug, FromBytes, IntoBytes, KnownLayout, Immutable, Serialize, Deserialize,
+    Error,
+    Validate,
+    Debug,
+    FromBytes,
+    IntoBytes,
+    KnownLayout,
+    Immutable,
+    Serialize,
+    Deserialize,
+    FxrSection,
 )]
+#[fxr(validate)]
+#[fxr(section = 14, array)]
 pub struct Section14Entry {
+    #[fxr(display = "hex", data)]
     data: u32,
 }
 
 #[repr(C)]
-#[derive(Error, Debug, FromBytes, IntoBytes, KnownLayout, Immutable, Serialize, Deserialize)]
+#[derive(
+    Error, Debug, FromBytes, IntoBytes, KnownLayout, Immutable, Serialize, Deserialize, FxrSection,
+)]
+#[fxr(section = 8, array)]
 pub struct Section8Entry {
     unk00: u8,
     unk01: u8,
     unk02: u8,
     unk03: u8,
     unk04: u32,
+    #[fxr(display)]
     pub section11_count: u32,
+    #[fxr(display)]
     pub section9_count: u32,
+    #[serde(with = "hex_formatted_bytes")]
+    #[fxr(points_to = "Section11Entry", count = "section11_count")]
     pub section11_offset: u32,
     unk14: u32,
+    #[serde(with = "hex_formatted_bytes")]
+    #[fxr(points_to = "Section9Entry", count = "section9_count")]
     pub section9_offset: u32,
     unk1c: u32,
 }
 
 #[repr(C)]
-#[derive(Error, Debug, FromBytes, IntoBytes, KnownLayout, Immutable, Serialize, Deserialize)]
+#[derive(
+    Error, Debug, FromBytes, IntoBytes, KnownLayout, Immutable, Serialize, Deserialize, FxrSection,
+)]
+#[fxr(section = 9, array)]
 pub struct Section9Entry {
     unk00: u32,
     unk04: u32,
+    #[fxr(display)]
     pub section11_count: u32,
     unk0c: u32,
+    #[serde(with = "hex_formatted_bytes")]
+    #[fxr(display = "hex")]
+    #[fxr(points_to = "Section11Entry", count = "section11_count")]
     pub section11_offset: u32,
     unk14: u32,
 }
-
-impl Display for Header {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "Header {{ magic: 0x{:X}, version: {}, ffx_id: {}, ... }}",
-            self.magic, self.version, self.ffx_id
-        )
-    }
-}
-
-impl Display for Section1Container {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "Section1Container {{ section2_count: {}, section2_offset: 0x{:X} }}",
-            self.section2_count, self.section2_offset
-        )
-    }
-}
-
-impl Display for Section2Container {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "Section2Container {{ section3_count: {}, section3_offset: 0x{:X} }}",
-            self.section3_count, self.section3_offset
-        )
-    }
-}
-
-impl Display for Section3Entry {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "Section3Entry {{ section11_offset1: 0x{:X}, section11_offset2: 0x{:X} }}",
-            self.section11_offset1, self.section11_offset2
-        )
-    }
-}
-
-impl Display for Section4Container {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "Section4Container {{ section4_count: {}, section5_offset: 0x{:X}, section6_offset: 0x{:X} }}",
-            self.section4_count, self.section5_offset, self.section6_offset
-        )
-    }
-}
-
-impl Display for Section4Entry {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Section4Entry {{ unk00: 0x{:X} }}", self.unk00)
-    }
-}
-
-impl Display for Section5Entry {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Section5Entry {{ unk00: 0x{:X} }}", self.unk00)
-    }
-}
-
-impl Display for Section6Entry {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "Section6Entry {{ section11_offset: 0x{:X}, section10_offset: 0x{:X}, section7_offset: 0x{:X} }}",
-            self.section11_offset, self.section10_offset, self.section7_offset
-        )
-    }
-}
-
-impl Display for Section7Container {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "Section7Container {{ section11_offset: 0x{:X}, section8_offset: 0x{:X} }}",
-            self.section11_offset, self.section8_offset
-        )
-    }
-}
-
-impl Display for Section8Container {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "Section8Container {{ section11_offset: 0x{:X}, section9_offset: 0x{:X} }}",
-            self.section11_offset, self.section9_offset
-        )
-    }
-}
-
-impl Display for Section9Container {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "Section9Container {{ section11_offset: 0x{:X} }}",
-            self.section11_offset
-        )
-    }
-}
-
-impl Display for Section10Container {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "Section10Container {{ section11_offset: 0x{:X}, section11_count: {} }}",
-            self.section11_offset, self.section11_count
-        )
-    }
-}
-
-impl std::fmt::Display for Section11Entry {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Section11Entry {{ data: {} }}", self.data)
-    }
-}
-
-impl Display for Section12Entry {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Section12Entry {{ data: 0x{:X} }}", self.data)
-    }
-}
-
-impl Display for Section13Entry {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Section13Entry {{ data: 0x{:X} }}", self.data)
-    }
-}
-
-impl Display for Section14Entry {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Section14Entry {{ data: 0x{:X} }}", self.data)
-    }
-}
-
-impl Display for Section8Entry {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "Section8Entry {{ section11_count: {}, section9_count: {} }}",
-            self.section11_count, self.section9_count
-        )
-    }
-}
-
-impl Display for Section9Entry {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "Section9Entry {{ section11_count: {}, section11_offset: 0x{:X} }}",
-            self.section11_count, self.section11_offset
-        )
-    }
-}
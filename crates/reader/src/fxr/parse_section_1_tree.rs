@@ -4,6 +4,8 @@ use crate::fxr::{
     util::{parse_section_slice, parse_struct},
 };
 use log::debug;
+use serde::{Serialize, Serializer, ser::SerializeStruct};
+use std::ops::Deref;
 use validator::Validate;
 use zerocopy::Ref;
 
@@ -63,7 +65,7 @@ use zerocopy::Ref;
 pub fn parse_section1_tree(
     fxr_file_bytes: &[u8],
     offset: u32,
-) -> Result<ParsedSections, Box<dyn std::error::Error>> {
+) -> Result<ParsedSections<'_>, Box<dyn std::error::Error>> {
     let section1 = parse_struct::<Section1Container>(fxr_file_bytes, offset, "Section1")?;
     debug!("Section1 @ 0x{:08X}: {:#?}", offset, section1);
 
@@ -105,6 +107,16 @@ pub struct ParsedSections<'a> {
     pub section3: Option<Ref<&'a [u8], [Section3Entry]>>, // Assuming Section3 is a collection
 }
 
+impl Serialize for ParsedSections<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("ParsedSections", 3)?;
+        state.serialize_field("section1", self.section1.deref())?;
+        state.serialize_field("section2", &self.section2.as_deref())?;
+        state.serialize_field("section3", &self.section3.as_deref())?;
+        state.end()
+    }
+}
+
 impl Validate for ParsedSections<'_> {
     fn validate(&self) -> Result<(), validator::ValidationErrors> {
         self.section1.validate()?;
@@ -1,9 +1,11 @@
 use crate::fxr::{
     Section4Container, Section4Entry, Section5Entry, Section6Entry,
-    parse_section_6_nested::parse_section6_nested,
+    parse_section_6_nested::{ParsedSection6, parse_section6_nested_with},
     util::{parse_section_slice, parse_struct},
 };
 use log::debug;
+use serde::{Serialize, Serializer, ser::SerializeStruct};
+use std::ops::Deref;
 use validator::Validate;
 use zerocopy::Ref;
 
@@ -83,7 +85,17 @@ use zerocopy::Ref;
 pub fn parse_section4_tree(
     fxr_file_bytes: &[u8],
     offset: u32,
-) -> Result<ParsedSection4Tree, Box<dyn std::error::Error>> {
+) -> Result<ParsedSection4Tree<'_>, Box<dyn std::error::Error>> {
+    parse_section4_tree_with(fxr_file_bytes, offset, true)
+}
+
+/// [`parse_section4_tree`], running the `Validate` rules checked while parsing Section6 only when
+/// `validate` is set
+pub(crate) fn parse_section4_tree_with(
+    fxr_file_bytes: &[u8],
+    offset: u32,
+    validate: bool,
+) -> Result<ParsedSection4Tree<'_>, Box<dyn std::error::Error>> {
     let container = parse_struct::<Section4Container>(fxr_file_bytes, offset, "Section4Container")?;
     debug!("Section4Container @ 0x{:08X}: {:#?}", offset, container);
 
@@ -111,6 +123,7 @@ pub fn parse_section4_tree(
         None
     };
 
+    let mut section6_nested = Vec::new();
     let section6_entries = if container.section6_count > 0 {
         let entries = parse_section_slice::<Section6Entry>(
             fxr_file_bytes,
@@ -121,7 +134,12 @@ pub fn parse_section4_tree(
         for (i, entry) in entries.iter().enumerate() {
             let ptr = entry as *const _ as usize - fxr_file_bytes.as_ptr() as usize;
             debug!("Section6[{}] @ 0x{:08X}: {:#?}", i, ptr, entry);
-            parse_section6_nested(fxr_file_bytes, entry, i)?;
+            section6_nested.push(parse_section6_nested_with(
+                fxr_file_bytes,
+                entry,
+                i,
+                validate,
+            )?);
         }
         Some(entries)
     } else {
@@ -133,6 +151,7 @@ pub fn parse_section4_tree(
         section4_entries,
         section5_entries,
         section6_entries,
+        section6_nested,
     })
 }
 
@@ -142,6 +161,20 @@ pub struct ParsedSection4Tree<'a> {
     pub section4_entries: Option<zerocopy::Ref<&'a [u8], [Section4Entry]>>,
     pub section5_entries: Option<zerocopy::Ref<&'a [u8], [Section5Entry]>>,
     pub section6_entries: Option<zerocopy::Ref<&'a [u8], [Section6Entry]>>,
+    /// Nested sections of each `Section6Entry`, in the same order as `section6_entries`
+    pub section6_nested: Vec<ParsedSection6<'a>>,
+}
+
+impl Serialize for ParsedSection4Tree<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("ParsedSection4Tree", 5)?;
+        state.serialize_field("container", self.container.deref())?;
+        state.serialize_field("section4_entries", &self.section4_entries.as_deref())?;
+        state.serialize_field("section5_entries", &self.section5_entries.as_deref())?;
+        state.serialize_field("section6_entries", &self.section6_entries.as_deref())?;
+        state.serialize_field("section6_nested", &self.section6_nested)?;
+        state.end()
+    }
 }
 
 impl ParsedSection4Tree<'_> {
@@ -3,10 +3,12 @@ use crate::fxr::{
     util::{ParseError, parse_section_slice, parse_struct},
 };
 use log::debug;
+use serde::{Serialize, Serializer, ser::SerializeStruct};
+use std::ops::Deref;
 use validator::Validate;
 use zerocopy::Ref;
 pub mod parse_section_7_nested;
-use parse_section_7_nested::parse_section7_nested;
+use parse_section_7_nested::{ParsedSection7Nested, parse_section7_nested_with};
 
 #[derive(Debug)]
 pub struct ParsedSection6<'a> {
@@ -24,6 +26,35 @@ pub struct ParsedSection10<'a> {
 #[derive(Debug)]
 pub struct ParsedSection7<'a> {
     pub container: Ref<&'a [u8], Section7Container>,
+    pub nested: ParsedSection7Nested<'a>,
+}
+
+impl Serialize for ParsedSection6<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("ParsedSection6", 3)?;
+        state.serialize_field("section11", &self.section11.as_deref())?;
+        state.serialize_field("section10", &self.section10)?;
+        state.serialize_field("section7", &self.section7)?;
+        state.end()
+    }
+}
+
+impl Serialize for ParsedSection10<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("ParsedSection10", 2)?;
+        state.serialize_field("container", self.container.deref())?;
+        state.serialize_field("section11", &self.section11.as_deref())?;
+        state.end()
+    }
+}
+
+impl Serialize for ParsedSection7<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("ParsedSection7", 2)?;
+        state.serialize_field("container", self.container.deref())?;
+        state.serialize_field("nested", &self.nested)?;
+        state.end()
+    }
 }
 
 /// Parses nested sections within Section6
@@ -74,6 +105,17 @@ pub fn parse_section6_nested<'a>(
     data: &'a [u8],
     entry: &crate::fxr::Section6Entry,
     index: usize,
+) -> Result<ParsedSection6<'a>, ParseError> {
+    parse_section6_nested_with(data, entry, index, true)
+}
+
+/// [`parse_section6_nested`], running the Section7 and Section11 `Validate` rules only when
+/// `validate` is set
+pub(crate) fn parse_section6_nested_with<'a>(
+    data: &'a [u8],
+    entry: &crate::fxr::Section6Entry,
+    index: usize,
+    validate: bool,
 ) -> Result<ParsedSection6<'a>, ParseError> {
     debug!("Parsing nested sections in Section6[{}]", index);
 
@@ -195,14 +237,17 @@ pub fn parse_section6_nested<'a>(
             ),
         )?;
         let ptr = entry as *const _ as usize - data.as_ptr() as usize;
-        parse_section7_nested(
+        let nested = parse_section7_nested_with(
             data,
             &container,
             &format!("Section6[{}]::Section7 @ 0x{:08X}", index, ptr),
+            validate,
         )?;
-        container.validate()?;
+        if validate {
+            container.validate()?;
+        }
 
-        parsed_section6.section7 = Some(ParsedSection7 { container });
+        parsed_section6.section7 = Some(ParsedSection7 { container, nested });
     } else {
         debug!(
             "  Skipping Section7 parsing for Section6[{}]: section7_count1 is 0",
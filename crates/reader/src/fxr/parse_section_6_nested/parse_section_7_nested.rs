@@ -1,5 +1,6 @@
 use crate::fxr::{Section8Entry, Section9Entry, Section11Entry, util::parse_section_slice};
 use log::debug;
+use serde::{Serialize, Serializer, ser::SerializeStruct};
 use std::fmt::{Display, Formatter};
 use thiserror::Error;
 use validator::Validate;
@@ -10,6 +11,14 @@ pub struct ParsedSection9<'a> {
     pub section11: Vec<Ref<&'a [u8], [Section11Entry]>>,
 }
 
+impl Serialize for ParsedSection9<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("ParsedSection9", 1)?;
+        state.serialize_field("section11", &section11_slices(&self.section11))?;
+        state.end()
+    }
+}
+
 impl Display for ParsedSection9<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "ParsedSection9 {{ section11: {:?} }}", self.section11)
@@ -28,9 +37,20 @@ impl Validate for ParsedSection9<'_> {
 #[derive(Error, Debug)]
 pub struct ParsedSection8<'a> {
     pub section11: Vec<Ref<&'a [u8], [Section11Entry]>>,
+    pub section9_entries: Option<Ref<&'a [u8], [Section9Entry]>>,
     pub section9: Vec<ParsedSection9<'a>>,
 }
 
+impl Serialize for ParsedSection8<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("ParsedSection8", 3)?;
+        state.serialize_field("section11", &section11_slices(&self.section11))?;
+        state.serialize_field("section9_entries", &self.section9_entries.as_deref())?;
+        state.serialize_field("section9", &self.section9)?;
+        state.end()
+    }
+}
+
 impl Display for ParsedSection8<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -55,9 +75,26 @@ impl Validate for ParsedSection8<'_> {
 #[derive(Error, Debug)]
 pub struct ParsedSection7Nested<'a> {
     pub section11: Vec<Ref<&'a [u8], [Section11Entry]>>,
+    pub section8_entries: Option<Ref<&'a [u8], [Section8Entry]>>,
     pub section8: Vec<ParsedSection8<'a>>,
 }
 
+impl Serialize for ParsedSection7Nested<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("ParsedSection7Nested", 3)?;
+        state.serialize_field("section11", &section11_slices(&self.section11))?;
+        state.serialize_field("section8_entries", &self.section8_entries.as_deref())?;
+        state.serialize_field("section8", &self.section8)?;
+        state.end()
+    }
+}
+
+fn section11_slices<'r>(
+    section11: &'r [Ref<&[u8], [Section11Entry]>],
+) -> Vec<&'r [Section11Entry]> {
+    section11.iter().map(|entries| &**entries).collect()
+}
+
 impl Display for ParsedSection7Nested<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -247,13 +284,25 @@ pub fn parse_section7_nested<'a>(
     data: &'a [u8],
     container: &crate::fxr::Section7Container,
     label: &str,
+) -> Result<ParsedSection7Nested<'a>, Box<dyn std::error::Error>> {
+    parse_section7_nested_with(data, container, label, true)
+}
+
+/// [`parse_section7_nested`], running the Section11 `Validate` rules only when `validate` is set
+pub(crate) fn parse_section7_nested_with<'a>(
+    data: &'a [u8],
+    container: &crate::fxr::Section7Container,
+    label: &str,
+    validate: bool,
 ) -> Result<ParsedSection7Nested<'a>, Box<dyn std::error::Error>> {
     debug!("{}: Parsing Section7Container: {:#?}", label, container);
 
     let mut parsed_section7 = parse_section7_container(data, container, label)?;
 
     parse_section7_section8_entries(data, container, label, &mut parsed_section7)?;
-    parsed_section7.validate()?;
+    if validate {
+        parsed_section7.validate()?;
+    }
     Ok(parsed_section7)
 }
 fn parse_section7_section8_entries<'a>(
@@ -277,6 +326,7 @@ fn parse_section7_section8_entries<'a>(
         for (i, section8_entry) in section8_entry.iter().enumerate() {
             parse_section7_section8(data, label, i, section8_entry, parsed_section7)?;
         }
+        parsed_section7.section8_entries = Some(section8_entry);
     };
     Ok(())
 }
@@ -290,6 +340,7 @@ fn parse_section7_section8<'a>(
 ) -> Result<(), Box<dyn std::error::Error>> {
     let mut parsed_section8 = ParsedSection8 {
         section11: Vec::new(),
+        section9_entries: None,
         section9: Vec::new(),
     };
     parse_section8_section11_entries(data, label, i, entry, &mut parsed_section8)?;
@@ -323,6 +374,7 @@ fn parse_section9_entries<'a>(
         for (j, s9_entry) in section9_entries.iter().enumerate() {
             parse_section8_section9_entry(data, label, i, j, s9_entry, parsed_section8)?;
         }
+        parsed_section8.section9_entries = Some(section9_entries);
     };
     Ok(())
 }
@@ -403,6 +455,7 @@ fn parse_section7_container<'a>(
 ) -> Result<ParsedSection7Nested<'a>, Box<dyn std::error::Error>> {
     let mut parsed_section7 = ParsedSection7Nested {
         section11: Vec::new(),
+        section8_entries: None,
         section8: Vec::new(),
     };
     parse_section7_section11_entries(data, container, label, &mut parsed_section7)?;
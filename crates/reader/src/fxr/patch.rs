@@ -0,0 +1,321 @@
+use super::{fxr_parser_with_sections::ParsedFXR, walk::walk};
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fs, path::Path};
+use thiserror::Error;
+
+/// One edit: the bytes at `offset` went from `old` to `new`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Change {
+    /// Struct and field the bytes belong to, e.g. `Section4::Section6[0].unk04`. Only used in
+    /// messages; the change is applied by offset.
+    pub path: String,
+    #[serde(with = "hex_offset")]
+    pub offset: usize,
+    #[serde(with = "hex_bytes")]
+    pub old: Vec<u8>,
+    #[serde(with = "hex_bytes")]
+    pub new: Vec<u8>,
+}
+
+/// Why a [`Patch`] couldn't be applied. Nothing is written when any change fails.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum PatchError {
+    #[error("{path} at 0x{offset:X} is past the end of the file")]
+    OutOfBounds { path: String, offset: usize },
+    #[error("{path} at 0x{offset:X} replaces {old} bytes with {new}")]
+    LengthMismatch {
+        path: String,
+        offset: usize,
+        old: usize,
+        new: usize,
+    },
+    #[error("{path} at 0x{offset:X} holds {found}, expected {expected}")]
+    Mismatch {
+        path: String,
+        offset: usize,
+        expected: String,
+        found: String,
+    },
+}
+
+/// A list of changes that can be saved and reapplied to another copy of the same file.
+///
+/// # Example
+/// ```rust
+/// use fxr_binary_reader::fxr::patch::{Change, Patch, PatchError};
+///
+/// let patch = Patch {
+///     changes: vec![Change {
+///         path: "Header.version".into(),
+///         offset: 6,
+///         old: vec![5, 0],
+///         new: vec![4, 0],
+///     }],
+/// };
+/// let json = patch.to_json().unwrap();
+/// assert!(json.contains(r#""offset": "0x6""#));
+/// assert!(json.contains(r#""old": "0500""#));
+/// assert_eq!(Patch::from_json(&json).unwrap(), patch);
+///
+/// let mut copy = vec![0u8; 8];
+/// copy[6] = 5;
+/// patch.apply(&mut copy).unwrap();
+/// assert_eq!(copy[6], 4);
+///
+/// // Applying it again finds the new value where the old one should be
+/// let error = patch.apply(&mut copy).unwrap_err();
+/// assert_eq!(error.to_string(), "Header.version at 0x6 holds 0400, expected 0500");
+/// assert!(matches!(error, PatchError::Mismatch { .. }));
+///
+/// // A change must keep the length of the bytes it replaces
+/// let mut grows = patch.clone();
+/// grows.changes[0].new = vec![4, 0, 0];
+/// let error = grows.apply(&mut copy).unwrap_err();
+/// assert_eq!(error.to_string(), "Header.version at 0x6 replaces 2 bytes with 3");
+///
+/// let mut past_end = patch.clone();
+/// past_end.changes[0].offset = usize::MAX;
+/// assert!(matches!(past_end.apply(&mut copy), Err(PatchError::OutOfBounds { .. })));
+///
+/// // Offsets are always hex
+/// let decimal = json.replace(r#""0x6""#, r#""6""#);
+/// assert!(Patch::from_json(&decimal).is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Patch {
+    pub changes: Vec<Change>,
+}
+
+impl Patch {
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        Ok(Self::from_json(&fs::read_to_string(path)?)?)
+    }
+
+    /// Applies the changes in order, checking that each one finds its old bytes first
+    /// # Arguments
+    /// * `bytes` - The file to patch, left untouched if any change doesn't match
+    pub fn apply(&self, bytes: &mut [u8]) -> Result<(), PatchError> {
+        let mut patched = bytes.to_vec();
+        for change in &self.changes {
+            if change.new.len() != change.old.len() {
+                return Err(PatchError::LengthMismatch {
+                    path: change.path.clone(),
+                    offset: change.offset,
+                    old: change.old.len(),
+                    new: change.new.len(),
+                });
+            }
+            let found = span_mut(&mut patched, &change.path, change.offset, change.old.len())?;
+            if *found != *change.old {
+                return Err(PatchError::Mismatch {
+                    path: change.path.clone(),
+                    offset: change.offset,
+                    expected: hex_bytes::encode(&change.old),
+                    found: hex_bytes::encode(found),
+                });
+            }
+            found.copy_from_slice(&change.new);
+        }
+        bytes.copy_from_slice(&patched);
+        Ok(())
+    }
+}
+
+/// Edits made to a file, with undo and redo.
+///
+/// # Example
+/// ```rust
+/// use fxr_binary_reader::fxr::patch::{Journal, PatchError};
+///
+/// let mut bytes = vec![0u8; 4];
+/// let mut journal = Journal::default();
+/// journal.write(&mut bytes, "Section11[0].data".into(), 0, &[1, 2]).unwrap();
+/// journal.write(&mut bytes, "Section11[0].data".into(), 1, &[3]).unwrap();
+/// assert_eq!(bytes, [1, 3, 0, 0]);
+///
+/// journal.undo(&mut bytes).unwrap();
+/// assert_eq!(bytes, [1, 2, 0, 0]);
+/// journal.redo(&mut bytes).unwrap();
+/// assert_eq!(bytes, [1, 3, 0, 0]);
+///
+/// // The patch holds the changes still in effect
+/// journal.undo(&mut bytes).unwrap();
+/// assert_eq!(journal.patch().changes.len(), 1);
+/// assert_eq!(journal.patch().changes[0].old, [0, 0]);
+///
+/// // Edits past the end are refused, and undo reports a change that no longer fits
+/// let error = journal.write(&mut bytes, "Section11[0].data".into(), 3, &[1, 2]);
+/// assert_eq!(error.unwrap_err().to_string(), "Section11[0].data at 0x3 is past the end of the file");
+/// assert!(matches!(journal.undo(&mut [0u8; 1]), Err(PatchError::OutOfBounds { .. })));
+/// assert_eq!(journal.undo(&mut bytes).unwrap().map(|change| change.offset), Some(0));
+/// assert_eq!(journal.undo(&mut bytes).unwrap(), None);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Journal {
+    done: Vec<Change>,
+    undone: Vec<Change>,
+}
+
+impl Journal {
+    /// Overwrites bytes and records the change, dropping anything that could be redone
+    /// # Arguments
+    /// * `bytes` - The file being edited
+    /// * `path` - Struct and field being changed, see [`describe_offset`]
+    /// * `offset` - Absolute offset of the first byte
+    /// * `new` - The bytes to write
+    /// # Returns
+    /// `PatchError::OutOfBounds` if the bytes don't fit in the file, which is left untouched
+    pub fn write(
+        &mut self,
+        bytes: &mut [u8],
+        path: String,
+        offset: usize,
+        new: &[u8],
+    ) -> Result<(), PatchError> {
+        let target = span_mut(bytes, &path, offset, new.len())?;
+        self.done.push(Change {
+            path,
+            offset,
+            old: target.to_vec(),
+            new: new.to_vec(),
+        });
+        self.undone.clear();
+        target.copy_from_slice(new);
+        Ok(())
+    }
+
+    /// Restores the bytes of the last change
+    /// # Returns
+    /// The change that was undone, or `None` if there is nothing to undo. A change that no
+    /// longer fits in `bytes` is an error and stays in the journal.
+    pub fn undo(&mut self, bytes: &mut [u8]) -> Result<Option<&Change>, PatchError> {
+        let Some(change) = self.done.last() else {
+            return Ok(None);
+        };
+        span_mut(bytes, &change.path, change.offset, change.old.len())?
+            .copy_from_slice(&change.old);
+        self.undone.extend(self.done.pop());
+        Ok(self.undone.last())
+    }
+
+    /// Writes the last undone change again
+    /// # Returns
+    /// The change that was redone, or `None` if there is nothing to redo. A change that no
+    /// longer fits in `bytes` is an error and stays in the journal.
+    pub fn redo(&mut self, bytes: &mut [u8]) -> Result<Option<&Change>, PatchError> {
+        let Some(change) = self.undone.last() else {
+            return Ok(None);
+        };
+        span_mut(bytes, &change.path, change.offset, change.new.len())?
+            .copy_from_slice(&change.new);
+        self.done.extend(self.undone.pop());
+        Ok(self.done.last())
+    }
+
+    /// The changes in effect, oldest first
+    pub fn patch(&self) -> Patch {
+        Patch {
+            changes: self.done.clone(),
+        }
+    }
+}
+
+/// The `len` bytes at `offset`, or `PatchError::OutOfBounds` naming `path` if they run past the end
+fn span_mut<'a>(
+    bytes: &'a mut [u8],
+    path: &str,
+    offset: usize,
+    len: usize,
+) -> Result<&'a mut [u8], PatchError> {
+    offset
+        .checked_add(len)
+        .and_then(|end| bytes.get_mut(offset..end))
+        .ok_or_else(|| PatchError::OutOfBounds {
+            path: path.into(),
+            offset,
+        })
+}
+
+/// Names the struct field that holds a byte, e.g. `Section4::Section6[0].unk04`
+///
+/// # Example
+/// ```rust
+/// use fxr_binary_reader::fxr::{fxr_parser_with_sections::parse_fxr, patch::describe_offset};
+///
+/// let mut data = vec![0u8; 0xA0];
+/// data[0..4].copy_from_slice(b"FXR\0");
+/// data[6..8].copy_from_slice(&5u16.to_le_bytes()); // version
+/// data[8..12].copy_from_slice(&1u32.to_le_bytes()); // unk08
+/// data[0x10..0x14].copy_from_slice(&0x90u32.to_le_bytes()); // section1_offset
+/// data[0x14..0x18].copy_from_slice(&1u32.to_le_bytes()); // section1_count
+/// data[0x68..0x6C].copy_from_slice(&1u32.to_le_bytes()); // unk68
+///
+/// let fxr = parse_fxr(&data).unwrap();
+/// assert_eq!(describe_offset(&fxr, 7).as_deref(), Some("Header.version"));
+/// assert_eq!(describe_offset(&fxr, 0x94).as_deref(), Some("Section1.section2_count"));
+/// ```
+pub fn describe_offset(fxr: &ParsedFXR, offset: usize) -> Option<String> {
+    walk(fxr).into_iter().find_map(|node| {
+        let relative = offset.checked_sub(node.offset)?;
+        let span = node
+            .section
+            .layout()
+            .iter()
+            .find(|span| span.range().contains(&relative))?;
+        Some(format!("{}.{}", node.path, span.name))
+    })
+}
+
+/// Offsets written as `"0x1A0"`
+mod hex_offset {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(offset: &usize, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("0x{:X}", offset))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<usize, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        let digits = s.strip_prefix("0x").ok_or_else(|| {
+            serde::de::Error::custom(format!("offset `{}` must start with 0x", s))
+        })?;
+        usize::from_str_radix(digits, 16).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Byte strings written as hex digits in file order, e.g. `"0000803F"`
+mod hex_bytes {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn encode(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02X}", byte)).collect()
+    }
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        if s.len() % 2 != 0 {
+            return Err(serde::de::Error::custom("odd number of hex digits"));
+        }
+        (0..s.len())
+            .step_by(2)
+            .map(|i| {
+                let digits = s
+                    .get(i..i + 2)
+                    .ok_or_else(|| serde::de::Error::custom("expected hex digits"))?;
+                u8::from_str_radix(digits, 16).map_err(serde::de::Error::custom)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,635 @@
+use super::{
+    Section11Entry,
+    fxr_parser_with_sections::ParsedFXR,
+    layout::struct_for,
+    walk::{Node, PathSegment, walk},
+};
+use std::{cmp::Ordering, str::FromStr};
+use thiserror::Error;
+
+/// A syntax error in a query, with the 1-based column it was found at
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("column {column}: {message}")]
+pub struct QueryError {
+    pub column: usize,
+    pub message: String,
+}
+
+/// A compiled query selecting nodes of the parsed tree.
+///
+/// A query is a path pattern, optionally followed by `where` and a condition:
+///
+/// ```text
+/// Section4::Section6[*] where unk00 == 603 && Section11[4] > 2.0
+/// **::Section8[0..2] where Section9[*].unk00 != 0
+/// Header where magic == "FXR\0"
+/// ```
+///
+/// Path steps are `Header`, `Section<N>` or `*` for any section, separated by `::`, and `**`
+/// matches any number of steps. Array sections take an index, `[*]` or a half-open range
+/// `[0..4]`; single containers such as `Section4` or `Section7` take none, so `Section4` and
+/// `Section4[*]` select different structs.
+///
+/// Conditions compare a field of the matched node (`unk00`), a Section11 slot below it
+/// (`Section11[4]`) or a field further down (`Section7::Section8[*].unk00`) against a literal,
+/// combined with `&&`, `||`, `!` and parentheses. When the operand is a wildcard path, the
+/// comparison holds if it holds for any node it reaches. The literal decides how the bytes are
+/// read:
+/// - `603`, `-1`: integers. Section11 slots and comparisons with negative numbers are signed.
+/// - `0x3F800000`: the raw bits, unsigned.
+/// - `2.0`, `2.0f`, `1e-3`: the bits of a 4-byte field read as an `f32`.
+/// - `"FXR\0"`: the raw bytes, for `==` and `!=`.
+///
+/// # Example
+/// ```rust
+/// use fxr_binary_reader::fxr::{fxr_parser_with_sections::parse_fxr, query::Query};
+///
+/// let mut data = vec![0u8; 0xA0];
+/// data[0..4].copy_from_slice(b"FXR\0");
+/// data[6..8].copy_from_slice(&5u16.to_le_bytes()); // version
+/// data[8..12].copy_from_slice(&1u32.to_le_bytes()); // unk08
+/// data[0x10..0x14].copy_from_slice(&0x90u32.to_le_bytes()); // section1_offset
+/// data[0x14..0x18].copy_from_slice(&1u32.to_le_bytes()); // section1_count
+/// data[0x68..0x6C].copy_from_slice(&1u32.to_le_bytes()); // unk68
+/// let fxr = parse_fxr(&data).unwrap();
+///
+/// let query: Query = "* where section2_count == 0 && unk00 < 5".parse().unwrap();
+/// let matches = query.select(&fxr);
+/// assert_eq!(matches.len(), 1);
+/// assert_eq!(matches[0].path.to_string(), "Section1");
+/// assert_eq!(matches[0].offset, 0x90);
+///
+/// let error = "Section6[*] where unk00 ==".parse::<Query>().unwrap_err();
+/// assert_eq!(error.to_string(), "column 27: expected a value");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    pattern: Vec<Step>,
+    condition: Option<Condition>,
+}
+
+impl Query {
+    /// Returns every node that matches, in tree order
+    pub fn select<'r>(&self, fxr: &'r ParsedFXR<'_>) -> Vec<Node<'r>> {
+        let nodes = walk(fxr);
+        nodes
+            .iter()
+            .filter(|node| matches_path(&self.pattern, node.path.segments()))
+            .filter(|node| {
+                self.condition
+                    .as_ref()
+                    .is_none_or(|condition| condition.holds(node, &nodes))
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+impl FromStr for Query {
+    type Err = QueryError;
+
+    fn from_str(query: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            tokens: tokenize(query)?,
+            position: 0,
+        };
+        let query = parser.query()?;
+        let token = parser.next();
+        if token.token != Token::End {
+            return Err(token.error("expected `where`, `::` or the end of the query"));
+        }
+        Ok(query)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    /// `**`
+    Descendants,
+    /// `Section6[*]`, `*`, `Header`. `section` is `None` for `*`.
+    Segment {
+        section: Option<u8>,
+        index: IndexPattern,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IndexPattern {
+    /// No brackets: a single container, or anything for `*`
+    Unindexed,
+    Any,
+    Exact(usize),
+    Range(usize, usize),
+}
+
+impl Step {
+    fn matches(&self, segment: &PathSegment) -> bool {
+        let Step::Segment { section, index } = self else {
+            return true;
+        };
+        if section.is_some_and(|section| section != segment.section) {
+            return false;
+        }
+        match (index, segment.index) {
+            (IndexPattern::Unindexed, None) => true,
+            (IndexPattern::Unindexed, Some(_)) => section.is_none(),
+            (_, None) => false,
+            (IndexPattern::Any, Some(_)) => true,
+            (IndexPattern::Exact(expected), Some(i)) => i == *expected,
+            (IndexPattern::Range(start, end), Some(i)) => (*start..*end).contains(&i),
+        }
+    }
+}
+
+fn matches_path(steps: &[Step], segments: &[PathSegment]) -> bool {
+    match steps.split_first() {
+        None => segments.is_empty(),
+        Some((Step::Descendants, rest)) => {
+            (0..=segments.len()).any(|skip| matches_path(rest, &segments[skip..]))
+        }
+        Some((step, rest)) => segments
+            .split_first()
+            .is_some_and(|(first, tail)| step.matches(first) && matches_path(rest, tail)),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Condition {
+    Or(Box<Condition>, Box<Condition>),
+    And(Box<Condition>, Box<Condition>),
+    Not(Box<Condition>),
+    Compare {
+        operand: Operand,
+        op: Op,
+        literal: Literal,
+    },
+}
+
+impl Condition {
+    fn holds(&self, node: &Node, nodes: &[Node]) -> bool {
+        match self {
+            Condition::Or(a, b) => a.holds(node, nodes) || b.holds(node, nodes),
+            Condition::And(a, b) => a.holds(node, nodes) && b.holds(node, nodes),
+            Condition::Not(a) => !a.holds(node, nodes),
+            Condition::Compare {
+                operand,
+                op,
+                literal,
+            } => operand.targets(node, nodes).into_iter().any(|target| {
+                operand
+                    .value(target)
+                    .is_some_and(|(bytes, signed)| literal.compare(bytes, signed, *op))
+            }),
+        }
+    }
+}
+
+/// A value reached from the matched node: a relative path and/or a field name
+#[derive(Debug, Clone, PartialEq)]
+struct Operand {
+    path: Vec<Step>,
+    field: Option<String>,
+}
+
+impl Operand {
+    fn targets<'n, 'r>(&self, node: &'n Node<'r>, nodes: &'n [Node<'r>]) -> Vec<&'n Node<'r>> {
+        if self.path.is_empty() {
+            return vec![node];
+        }
+        let depth = node.path.segments().len();
+        nodes
+            .iter()
+            .filter(|other| {
+                other.path.starts_with(&node.path)
+                    && matches_path(&self.path, &other.path.segments()[depth..])
+            })
+            .collect()
+    }
+
+    /// Raw bytes of the operand on `target`, and whether they hold a signed value
+    fn value<'r>(&self, target: &Node<'r>) -> Option<(&'r [u8], bool)> {
+        let bytes = target.section.as_bytes();
+        match &self.field {
+            Some(field) => {
+                let field = target
+                    .section
+                    .reflect()
+                    .into_iter()
+                    .find(|reflected| reflected.name == field)?;
+                let signed = target.section.get::<Section11Entry>().is_some();
+                Some((field.bytes, signed))
+            }
+            None => target
+                .section
+                .get::<Section11Entry>()
+                .is_some()
+                .then_some((bytes, true)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Op {
+    fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            Op::Eq => ordering == Ordering::Equal,
+            Op::Ne => ordering != Ordering::Equal,
+            Op::Lt => ordering == Ordering::Less,
+            Op::Le => ordering != Ordering::Greater,
+            Op::Gt => ordering == Ordering::Greater,
+            Op::Ge => ordering != Ordering::Less,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Literal {
+    Int(i64),
+    Bits(u64),
+    Float(f32),
+    Bytes(Vec<u8>),
+}
+
+impl Literal {
+    fn compare(&self, bytes: &[u8], signed: bool, op: Op) -> bool {
+        let mut le = [0u8; 8];
+        le[..bytes.len()].copy_from_slice(bytes);
+        let unsigned = u64::from_le_bytes(le);
+        let ordering = match self {
+            Literal::Int(value) if signed || *value < 0 => {
+                let shift = 64 - bytes.len() * 8;
+                (((unsigned << shift) as i64) >> shift).partial_cmp(value)
+            }
+            Literal::Int(value) => (unsigned as i64).partial_cmp(value),
+            Literal::Bits(value) => unsigned.partial_cmp(value),
+            Literal::Float(value) if bytes.len() == 4 => {
+                f32::from_bits(unsigned as u32).partial_cmp(value)
+            }
+            Literal::Float(_) => None,
+            Literal::Bytes(value) if matches!(op, Op::Eq | Op::Ne) => {
+                Some(bytes.cmp(value.as_slice()))
+            }
+            Literal::Bytes(_) => None,
+        };
+        ordering.is_some_and(|ordering| op.holds(ordering))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(String),
+    Str(Vec<u8>),
+    Symbol(&'static str),
+    End,
+}
+
+#[derive(Debug, Clone)]
+struct Spanned {
+    token: Token,
+    column: usize,
+}
+
+impl Spanned {
+    fn error(&self, message: impl Into<String>) -> QueryError {
+        QueryError {
+            column: self.column,
+            message: message.into(),
+        }
+    }
+}
+
+/// Longest symbols first so `**` isn't read as two `*`
+const SYMBOLS: [&str; 17] = [
+    "::", "**", "..", "==", "!=", "<=", ">=", "&&", "||", "*", "[", "]", ".", "(", ")", "<", ">",
+];
+
+fn tokenize(query: &str) -> Result<Vec<Spanned>, QueryError> {
+    let chars: Vec<char> = query.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let start = i;
+        let error = |message: String| QueryError {
+            column: start + 1,
+            message,
+        };
+        let c = chars[i];
+        let rest: String = chars[i..].iter().take(2).collect();
+        let token = if c.is_whitespace() {
+            i += 1;
+            continue;
+        } else if c.is_ascii_digit()
+            || (c == '-' && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit()))
+        {
+            i += 1;
+            while i < chars.len() {
+                let c = chars[i];
+                let exponent_sign = (c == '-' || c == '+') && matches!(chars[i - 1], 'e' | 'E');
+                let decimal_point = c == '.' && chars.get(i + 1) != Some(&'.');
+                if !(c.is_ascii_alphanumeric() || c == '_' || decimal_point || exponent_sign) {
+                    break;
+                }
+                i += 1;
+            }
+            Token::Number(chars[start..i].iter().collect())
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            Token::Ident(chars[start..i].iter().collect())
+        } else if c == '"' {
+            let (bytes, end) = string(&chars, i).map_err(error)?;
+            i = end;
+            Token::Str(bytes)
+        } else if c == '!' && !rest.starts_with("!=") {
+            i += 1;
+            Token::Symbol("!")
+        } else if let Some(symbol) = SYMBOLS.iter().find(|symbol| rest.starts_with(**symbol)) {
+            i += symbol.len();
+            Token::Symbol(symbol)
+        } else {
+            return Err(error(format!("unexpected character `{}`", c)));
+        };
+        tokens.push(Spanned {
+            token,
+            column: start + 1,
+        });
+    }
+    tokens.push(Spanned {
+        token: Token::End,
+        column: chars.len() + 1,
+    });
+    Ok(tokens)
+}
+
+/// Parses a string literal starting at the opening quote, returning its bytes and the index
+/// after the closing quote
+fn string(chars: &[char], start: usize) -> Result<(Vec<u8>, usize), String> {
+    let mut bytes = Vec::new();
+    let mut i = start + 1;
+    while i < chars.len() {
+        match chars[i] {
+            '"' => return Ok((bytes, i + 1)),
+            '\\' => {
+                match chars.get(i + 1) {
+                    Some('0') => bytes.push(0),
+                    Some('\\') => bytes.push(b'\\'),
+                    Some('"') => bytes.push(b'"'),
+                    Some('x') => {
+                        let hex: String = chars.iter().skip(i + 2).take(2).collect();
+                        let byte = u8::from_str_radix(&hex, 16)
+                            .map_err(|_| format!("invalid escape `\\x{}`", hex))?;
+                        bytes.push(byte);
+                        i += 2;
+                    }
+                    _ => return Err("unknown escape".to_string()),
+                }
+                i += 2;
+            }
+            c if c.is_ascii() => {
+                bytes.push(c as u8);
+                i += 1;
+            }
+            c => return Err(format!("non-ASCII character `{}` in string", c)),
+        }
+    }
+    Err("unterminated string".to_string())
+}
+
+struct Parser {
+    tokens: Vec<Spanned>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.position].token
+    }
+
+    fn next(&mut self) -> Spanned {
+        let token = self.tokens[self.position].clone();
+        if token.token != Token::End {
+            self.position += 1;
+        }
+        token
+    }
+
+    fn eat(&mut self, symbol: &str) -> bool {
+        if matches!(self.peek(), Token::Symbol(found) if *found == symbol) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<(), QueryError> {
+        let token = self.next();
+        match token.token {
+            Token::Symbol(found) if found == symbol => Ok(()),
+            _ => Err(token.error(format!("expected `{}`", symbol))),
+        }
+    }
+
+    fn query(&mut self) -> Result<Query, QueryError> {
+        let pattern = self.path()?;
+        if pattern.is_empty() {
+            return Err(self
+                .next()
+                .error("expected a section such as `Section6[*]`"));
+        }
+        let condition = match self.peek() {
+            Token::Ident(word) if word == "where" => {
+                self.next();
+                Some(self.or()?)
+            }
+            _ => None,
+        };
+        Ok(Query { pattern, condition })
+    }
+
+    /// Zero or more `::`-separated steps
+    fn path(&mut self) -> Result<Vec<Step>, QueryError> {
+        let mut steps = Vec::new();
+        while let Some(step) = self.step()? {
+            steps.push(step);
+            if !self.eat("::") {
+                break;
+            }
+        }
+        Ok(steps)
+    }
+
+    fn step(&mut self) -> Result<Option<Step>, QueryError> {
+        let section = match self.peek().clone() {
+            Token::Symbol("**") => {
+                self.next();
+                return Ok(Some(Step::Descendants));
+            }
+            Token::Symbol("*") => None,
+            Token::Ident(word) if word == "Header" => Some(0),
+            Token::Ident(word) if word.starts_with("Section") => {
+                let token = &self.tokens[self.position];
+                let section = word["Section".len()..]
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|n| (1..=14).contains(n))
+                    .ok_or_else(|| token.error(format!("unknown section `{}`", word)))?;
+                Some(section)
+            }
+            _ => return Ok(None),
+        };
+        self.next();
+
+        let index = if self.eat("[") {
+            let index = if self.eat("*") {
+                IndexPattern::Any
+            } else {
+                let start = self.unsigned()?;
+                if self.eat("..") {
+                    IndexPattern::Range(start, self.unsigned()?)
+                } else {
+                    IndexPattern::Exact(start)
+                }
+            };
+            self.expect("]")?;
+            index
+        } else {
+            IndexPattern::Unindexed
+        };
+        if let Some(section) = section {
+            let indexed = index != IndexPattern::Unindexed;
+            let segment = PathSegment {
+                section,
+                index: indexed.then_some(0),
+            };
+            if struct_for(segment).is_none() {
+                let token = &self.tokens[self.position - 1];
+                let name = PathSegment {
+                    section,
+                    index: None,
+                };
+                let message = match indexed {
+                    true => format!("{} is a single struct and takes no index", name),
+                    false => format!("{} is an array and needs an index or `[*]`", name),
+                };
+                return Err(token.error(message));
+            }
+        }
+        Ok(Some(Step::Segment { section, index }))
+    }
+
+    fn unsigned(&mut self) -> Result<usize, QueryError> {
+        let token = self.next();
+        match &token.token {
+            Token::Number(word) => word
+                .parse()
+                .map_err(|_| token.error(format!("invalid index `{}`", word))),
+            _ => Err(token.error("expected an index")),
+        }
+    }
+
+    fn or(&mut self) -> Result<Condition, QueryError> {
+        let mut condition = self.and()?;
+        while self.eat("||") {
+            condition = Condition::Or(Box::new(condition), Box::new(self.and()?));
+        }
+        Ok(condition)
+    }
+
+    fn and(&mut self) -> Result<Condition, QueryError> {
+        let mut condition = self.unary()?;
+        while self.eat("&&") {
+            condition = Condition::And(Box::new(condition), Box::new(self.unary()?));
+        }
+        Ok(condition)
+    }
+
+    fn unary(&mut self) -> Result<Condition, QueryError> {
+        if self.eat("!") {
+            return Ok(Condition::Not(Box::new(self.unary()?)));
+        }
+        if self.eat("(") {
+            let condition = self.or()?;
+            self.expect(")")?;
+            return Ok(condition);
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Condition, QueryError> {
+        let start = self.tokens[self.position].clone();
+        let path = self.path()?;
+        let field = if path.is_empty() || self.eat(".") {
+            match self.next() {
+                Spanned {
+                    token: Token::Ident(field),
+                    ..
+                } => Some(field),
+                token => return Err(token.error("expected a field name")),
+            }
+        } else {
+            None
+        };
+        if let Some(field) = &field {
+            let known = (0..=14)
+                .flat_map(|section| [None, Some(0)].map(|index| PathSegment { section, index }))
+                .filter_map(struct_for)
+                .any(|(_, spans)| spans.iter().any(|span| span.name == field));
+            if !known {
+                return Err(start.error(format!("no section has a field `{}`", field)));
+            }
+        }
+
+        let op_token = self.next();
+        let op = match op_token.token {
+            Token::Symbol("==") => Op::Eq,
+            Token::Symbol("!=") => Op::Ne,
+            Token::Symbol("<") => Op::Lt,
+            Token::Symbol("<=") => Op::Le,
+            Token::Symbol(">") => Op::Gt,
+            Token::Symbol(">=") => Op::Ge,
+            _ => return Err(op_token.error("expected a comparison such as `==` or `>`")),
+        };
+        let literal = self.literal()?;
+        Ok(Condition::Compare {
+            operand: Operand { path, field },
+            op,
+            literal,
+        })
+    }
+
+    fn literal(&mut self) -> Result<Literal, QueryError> {
+        let token = self.next();
+        let word = match &token.token {
+            Token::Number(word) => word.replace('_', ""),
+            Token::Str(bytes) => return Ok(Literal::Bytes(bytes.clone())),
+            _ => return Err(token.error("expected a value")),
+        };
+        let invalid = || token.error(format!("invalid number `{}`", word));
+        if let Some(hex) = word.strip_prefix("0x").or_else(|| word.strip_prefix("0X")) {
+            return u64::from_str_radix(hex, 16)
+                .map(Literal::Bits)
+                .map_err(|_| invalid());
+        }
+        let float = word.strip_suffix('f');
+        if float.is_some() || word.contains(['.', 'e', 'E']) {
+            return float
+                .unwrap_or(&word)
+                .parse()
+                .map(Literal::Float)
+                .map_err(|_| invalid());
+        }
+        word.parse().map(Literal::Int).map_err(|_| invalid())
+    }
+}
@@ -0,0 +1,68 @@
+use super::{
+    fxr_parser_with_sections::ParsedFXR,
+    layout::FieldSpan,
+    walk::{SectionPath, walk},
+};
+
+/// One struct flattened into a row: where it is and the value of every field, private ones
+/// included
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub path: SectionPath,
+    /// Absolute offset of the struct in the file
+    pub offset: usize,
+    pub layout: &'static [FieldSpan],
+    /// Field values read as unsigned little-endian integers, in layout order
+    pub values: Vec<u64>,
+}
+
+impl Record {
+    /// Field names paired with their values
+    pub fn fields(&self) -> impl Iterator<Item = (&'static str, u64)> + '_ {
+        self.layout
+            .iter()
+            .map(|span| span.name)
+            .zip(self.values.iter().copied())
+    }
+}
+
+/// Flattens every struct of the given type (e.g. `Section6Entry`) in tree order.
+///
+/// # Arguments
+/// * `fxr` - The parsed file
+/// * `kind` - Struct name as reported by [`super::walk::SectionRef::kind`]
+///
+/// # Example
+/// ```rust
+/// use fxr_binary_reader::fxr::{fxr_parser_with_sections::parse_fxr, records::records};
+///
+/// let mut data = vec![0u8; 0xA0];
+/// data[0..4].copy_from_slice(b"FXR\0");
+/// data[6..8].copy_from_slice(&5u16.to_le_bytes()); // version
+/// data[8..12].copy_from_slice(&1u32.to_le_bytes()); // unk08
+/// data[0x10..0x14].copy_from_slice(&0x90u32.to_le_bytes()); // section1_offset
+/// data[0x14..0x18].copy_from_slice(&1u32.to_le_bytes()); // section1_count
+/// data[0x68..0x6C].copy_from_slice(&1u32.to_le_bytes()); // unk68
+/// let fxr = parse_fxr(&data).unwrap();
+///
+/// let rows: Vec<_> = records(&fxr, "Section1Container").collect();
+/// assert_eq!(rows.len(), 1);
+/// assert_eq!(rows[0].offset, 0x90);
+/// assert_eq!(rows[0].fields().next(), Some(("unk00", 0)));
+/// ```
+pub fn records<'r>(fxr: &'r ParsedFXR<'_>, kind: &'r str) -> impl Iterator<Item = Record> + 'r {
+    walk(fxr)
+        .into_iter()
+        .filter(move |node| node.section.kind() == kind)
+        .map(|node| Record {
+            layout: node.section.layout(),
+            values: node
+                .section
+                .reflect()
+                .iter()
+                .map(|field| field.value().as_u64())
+                .collect(),
+            path: node.path,
+            offset: node.offset,
+        })
+}
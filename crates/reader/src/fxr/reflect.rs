@@ -0,0 +1,144 @@
+use super::layout::{FieldLayout, FieldSpan};
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+use zerocopy::{Immutable, IntoBytes};
+
+/// Declared Rust type of a field. Every field in the format is an unsigned little-endian integer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    U8,
+    U16,
+    U32,
+}
+
+impl FieldType {
+    /// Type of a field with the given size in bytes
+    pub fn of_size(size: usize) -> Option<Self> {
+        match size {
+            1 => Some(FieldType::U8),
+            2 => Some(FieldType::U16),
+            4 => Some(FieldType::U32),
+            _ => None,
+        }
+    }
+
+    pub fn size(self) -> usize {
+        match self {
+            FieldType::U8 => 1,
+            FieldType::U16 => 2,
+            FieldType::U32 => 4,
+        }
+    }
+}
+
+impl Display for FieldType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            FieldType::U8 => "u8",
+            FieldType::U16 => "u16",
+            FieldType::U32 => "u32",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Value of a field, typed as declared
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(untagged)]
+pub enum FieldValue {
+    U8(u8),
+    U16(u16),
+    U32(u32),
+}
+
+impl FieldValue {
+    pub fn as_u64(self) -> u64 {
+        match self {
+            FieldValue::U8(value) => value.into(),
+            FieldValue::U16(value) => value.into(),
+            FieldValue::U32(value) => value.into(),
+        }
+    }
+}
+
+impl Display for FieldValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_u64())
+    }
+}
+
+/// One field of a struct, borrowed from the struct's bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Field<'a> {
+    pub name: &'static str,
+    /// Offset of the field from the start of the struct
+    pub offset: usize,
+    pub ty: FieldType,
+    pub bytes: &'a [u8],
+}
+
+impl Field<'_> {
+    pub fn value(&self) -> FieldValue {
+        match self.ty {
+            FieldType::U8 => FieldValue::U8(self.bytes[0]),
+            FieldType::U16 => FieldValue::U16(u16::from_le_bytes([self.bytes[0], self.bytes[1]])),
+            FieldType::U32 => FieldValue::U32(u32::from_le_bytes([
+                self.bytes[0],
+                self.bytes[1],
+                self.bytes[2],
+                self.bytes[3],
+            ])),
+        }
+    }
+}
+
+/// Field-by-field access to a section struct, private `unkXX` fields included, without going
+/// through serde.
+///
+/// # Example
+/// ```rust
+/// use fxr_binary_reader::fxr::{
+///     Section9Entry,
+///     reflect::{FieldType, FieldValue, Reflect},
+/// };
+/// use zerocopy::FromBytes;
+///
+/// let mut bytes = [0u8; 0x18];
+/// bytes[8..12].copy_from_slice(&3u32.to_le_bytes()); // section11_count
+/// let entry = Section9Entry::ref_from_bytes(&bytes).unwrap();
+///
+/// let count = entry.field("section11_count").unwrap();
+/// assert_eq!(Section9Entry::NAME, "Section9Entry");
+/// assert_eq!((count.offset, count.ty), (8, FieldType::U32));
+/// assert_eq!(count.value(), FieldValue::U32(3));
+/// assert_eq!(entry.fields().len(), 6);
+/// ```
+pub trait Reflect: FieldLayout + IntoBytes + Immutable {
+    /// Struct name, e.g. `Section6Entry`
+    const NAME: &'static str;
+
+    /// Every field in declaration order
+    fn fields(&self) -> Vec<Field<'_>> {
+        reflect(self.as_bytes(), Self::FIELDS)
+    }
+
+    fn field(&self, name: &str) -> Option<Field<'_>> {
+        self.fields().into_iter().find(|field| field.name == name)
+    }
+}
+
+/// Splits the bytes of a struct into fields following its layout
+pub fn reflect<'a>(bytes: &'a [u8], layout: &'static [FieldSpan]) -> Vec<Field<'a>> {
+    layout
+        .iter()
+        .filter_map(|span| {
+            Some(Field {
+                name: span.name,
+                offset: span.offset,
+                ty: FieldType::of_size(span.size)?,
+                bytes: &bytes[span.range()],
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,357 @@
+use super::{
+    catalog::section,
+    census::{Census, ValueCount},
+    fxr_parser_with_sections::ParsedFXR,
+    layout::FieldSpan,
+    walk::{SectionPath, is_offset_field, walk},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error,
+    fmt::{Display, Formatter},
+    fs,
+    path::Path,
+};
+
+/// The values a field is allowed to hold
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum Constraint {
+    /// Always zero
+    Zero,
+    /// One of a small set of values
+    OneOf { values: Vec<u64> },
+    /// Anything between `min` and `max`, inclusive
+    Range { min: u64, max: u64 },
+}
+
+impl Constraint {
+    pub fn allows(&self, value: u64) -> bool {
+        match self {
+            Constraint::Zero => value == 0,
+            Constraint::OneOf { values } => values.contains(&value),
+            Constraint::Range { min, max } => (*min..=*max).contains(&value),
+        }
+    }
+
+    /// Values just outside the constraint, used to find out whether a looser rule accepts them
+    fn neighbours(&self, type_max: u64) -> Vec<u64> {
+        let (min, max, gap) = match self {
+            Constraint::Zero => (0, 0, None),
+            Constraint::OneOf { values } => {
+                let gap = values
+                    .windows(2)
+                    .find(|pair| pair[1] > pair[0] + 1)
+                    .map(|pair| pair[0] + 1);
+                (values[0], values[values.len() - 1], gap)
+            }
+            Constraint::Range { min, max } => (*min, *max, None),
+        };
+        let mut probes: Vec<u64> = [min.checked_sub(1), gap, (max < type_max).then(|| max + 1)]
+            .into_iter()
+            .flatten()
+            .collect();
+        probes.dedup();
+        probes
+    }
+}
+
+impl Display for Constraint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Constraint::Zero => write!(f, "== 0"),
+            Constraint::OneOf { values } => {
+                let values: Vec<String> = values.iter().map(u64::to_string).collect();
+                write!(f, "in {{{}}}", values.join(", "))
+            }
+            Constraint::Range { min, max } => write!(f, "{}..={}", min, max),
+        }
+    }
+}
+
+/// A constraint on one field of one struct type
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldRule {
+    /// Struct name, e.g. `Section6Entry`
+    pub kind: String,
+    pub field: String,
+    #[serde(flatten)]
+    pub constraint: Constraint,
+}
+
+/// A named set of field rules, kept as JSON so each game can ship its own without rebuilding.
+///
+/// # Example
+/// ```rust
+/// use fxr_binary_reader::fxr::{fxr_parser_with_sections::parse_fxr, rules::Profile};
+///
+/// let profile = Profile::from_json(
+///     r#"{ "name": "example", "rules": [
+///         { "kind": "Header", "field": "version", "rule": "one_of", "values": [5] }
+///     ] }"#,
+/// )
+/// .unwrap();
+///
+/// let mut data = vec![0u8; 0xA0];
+/// data[0..4].copy_from_slice(b"FXR\0");
+/// data[6..8].copy_from_slice(&4u16.to_le_bytes()); // version
+/// data[8..12].copy_from_slice(&1u32.to_le_bytes()); // unk08
+/// data[0x10..0x14].copy_from_slice(&0x90u32.to_le_bytes()); // section1_offset
+/// data[0x14..0x18].copy_from_slice(&1u32.to_le_bytes()); // section1_count
+/// data[0x68..0x6C].copy_from_slice(&1u32.to_le_bytes()); // unk68
+///
+/// let violations = profile.check(&parse_fxr(&data).unwrap());
+/// assert_eq!(violations[0].to_string(), "Header.version = 4, expected in {5}");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    pub rules: Vec<FieldRule>,
+}
+
+impl Profile {
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        Ok(Self::from_json(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Returns every field in the file that breaks one of the rules, in tree order
+    pub fn check(&self, fxr: &ParsedFXR) -> Vec<RuleViolation> {
+        let mut by_kind: HashMap<&str, Vec<&FieldRule>> = HashMap::new();
+        for rule in &self.rules {
+            by_kind.entry(rule.kind.as_str()).or_default().push(rule);
+        }
+
+        let mut violations = Vec::new();
+        for node in walk(fxr) {
+            let Some(rules) = by_kind.get(node.section.kind()) else {
+                continue;
+            };
+            let fields = node.section.reflect();
+            for rule in rules {
+                let Some(field) = fields.iter().find(|field| field.name == rule.field) else {
+                    continue;
+                };
+                let value = field.value().as_u64();
+                if !rule.constraint.allows(value) {
+                    violations.push(RuleViolation {
+                        path: node.path.clone(),
+                        field: field.name,
+                        value,
+                        constraint: rule.constraint.clone(),
+                    });
+                }
+            }
+        }
+        violations
+    }
+}
+
+/// A field value that a [`Profile`] doesn't allow
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RuleViolation {
+    pub path: SectionPath,
+    pub field: &'static str,
+    pub value: u64,
+    pub constraint: Constraint,
+}
+
+impl Display for RuleViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}.{} = {}, expected {}",
+            self.path, self.field, self.value, self.constraint
+        )
+    }
+}
+
+/// Options for [`infer_profile`]
+#[derive(Debug, Clone, Copy)]
+pub struct InferOptions {
+    /// Fields with at most this many distinct values become [`Constraint::OneOf`], as do fields
+    /// with a single non-zero value whatever the limit
+    pub max_enum_values: usize,
+}
+
+impl Default for InferOptions {
+    fn default() -> Self {
+        Self { max_enum_values: 8 }
+    }
+}
+
+/// Proposes the tightest rule for every field that matches everything in the corpus.
+///
+/// Pointer fields (`*_offset`) are skipped since their values depend on where data happens to be.
+pub fn infer_profile(census: &Census, name: &str, options: InferOptions) -> Profile {
+    let mut rules = Vec::new();
+    for (kind, layout) in census.kinds() {
+        for span in layout {
+            if is_offset_field(span.name) {
+                continue;
+            }
+            let Some(values) = census.values(kind, span.name) else {
+                continue;
+            };
+            let mut distinct: Vec<u64> = values.to_vec();
+            distinct.sort_unstable();
+            distinct.dedup();
+            let constraint = match distinct.as_slice() {
+                [] => continue,
+                [0] => Constraint::Zero,
+                values if values.len() <= options.max_enum_values => Constraint::OneOf {
+                    values: values.to_vec(),
+                },
+                [value] => Constraint::OneOf {
+                    values: vec![*value],
+                },
+                [min, .., max] => Constraint::Range {
+                    min: *min,
+                    max: *max,
+                },
+            };
+            rules.push(FieldRule {
+                kind: kind.to_string(),
+                field: span.name.to_string(),
+                constraint,
+            });
+        }
+    }
+    Profile {
+        name: name.to_string(),
+        rules,
+    }
+}
+
+/// How a built-in `#[validate]` rule compares with what the corpus shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleStatus {
+    /// The validator agrees with the inferred rule
+    Matches,
+    /// The validator rejects values that occur in the corpus
+    Violated,
+    /// The validator has a rule, but it accepts values the corpus never uses
+    TooLoose,
+    /// The validator doesn't check this field at all
+    Unchecked,
+}
+
+/// One inferred rule next to the behaviour of the built-in validator for the same field
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleComparison {
+    pub kind: String,
+    pub field: String,
+    pub inferred: Constraint,
+    pub status: RuleStatus,
+    /// Corpus values the validator rejects, with how often they occur
+    pub rejected: Vec<ValueCount>,
+    /// Values outside the inferred rule that the validator accepts
+    pub accepted_unobserved: Vec<u64>,
+}
+
+/// Compares inferred rules with the `#[validate]` attributes on the section structs.
+///
+/// The attributes can't be read back at runtime, so each field is probed instead: a sample
+/// struct from the corpus is run through `validate()` with the field set to each corpus value
+/// and to values just outside the inferred rule.
+pub fn compare_with_validators(census: &Census, profile: &Profile) -> Vec<RuleComparison> {
+    let mut comparisons = Vec::new();
+    for rule in &profile.rules {
+        let (Some(sample), Some(values)) = (
+            census.sample(&rule.kind),
+            census.values(&rule.kind, &rule.field),
+        ) else {
+            continue;
+        };
+        let Some(span) = census
+            .kinds()
+            .find(|(kind, _)| *kind == rule.kind)
+            .and_then(|(_, layout)| layout.iter().find(|span| span.name == rule.field))
+        else {
+            continue;
+        };
+        let probe = Probe {
+            kind: &rule.kind,
+            sample,
+            span,
+        };
+
+        let mut counts: BTreeMap<u64, usize> = BTreeMap::new();
+        for value in values {
+            *counts.entry(*value).or_default() += 1;
+        }
+        let rejected: Vec<ValueCount> = counts
+            .into_iter()
+            .filter(|(value, _)| !probe.accepts(*value))
+            .map(|(value, count)| ValueCount { value, count })
+            .collect();
+
+        let type_max = u64::MAX >> (64 - span.size * 8);
+        let accepted_unobserved: Vec<u64> = rule
+            .constraint
+            .neighbours(type_max)
+            .into_iter()
+            .filter(|value| probe.accepts(*value))
+            .collect();
+        let checked = [0, 1, type_max]
+            .into_iter()
+            .chain(rule.constraint.neighbours(type_max))
+            .any(|value| !probe.accepts(value));
+
+        let status = if !rejected.is_empty() {
+            RuleStatus::Violated
+        } else if accepted_unobserved.is_empty() {
+            RuleStatus::Matches
+        } else if checked {
+            RuleStatus::TooLoose
+        } else {
+            RuleStatus::Unchecked
+        };
+        comparisons.push(RuleComparison {
+            kind: rule.kind.clone(),
+            field: rule.field.clone(),
+            inferred: rule.constraint.clone(),
+            status,
+            rejected,
+            accepted_unobserved,
+        });
+    }
+    comparisons
+}
+
+/// Runs the built-in validator on a sample struct with one field overwritten
+struct Probe<'a> {
+    kind: &'a str,
+    sample: &'a [u8],
+    span: &'a FieldSpan,
+}
+
+impl Probe<'_> {
+    fn accepts(&self, value: u64) -> bool {
+        let mut bytes = self.sample.to_vec();
+        bytes[self.span.range()].copy_from_slice(&value.to_le_bytes()[..self.span.size]);
+        !failing_fields(self.kind, &bytes).contains(&self.span.name.to_string())
+    }
+}
+
+/// Names of the fields that fail validation for a struct of the given type. Structs without
+/// `#[fxr(validate)]` accept everything, so their fields are reported as unchecked.
+fn failing_fields(kind: &str, bytes: &[u8]) -> Vec<String> {
+    match section(kind).map(|info| (info.validate)(bytes)) {
+        Some(Err(errors)) => errors
+            .field_errors()
+            .keys()
+            .map(|field| field.to_string())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
@@ -0,0 +1,322 @@
+use super::{
+    catalog::{Pointer, SectionInfo, section, sections},
+    layout::FieldSpan,
+    reflect::FieldType,
+};
+use serde_json::{Map, Value, json};
+use std::fmt::Write;
+
+/// ImHex pattern language source describing a whole file, starting with the header at offset 0.
+///
+/// Every struct is declared from its `#[derive(FxrSection)]` layout, and every
+/// `#[fxr(points_to = ...)]` offset is followed with a placed variable guarded by its count, the
+/// same way the parser follows it.
+///
+/// # Example
+/// ```rust
+/// use fxr_binary_reader::fxr::templates::imhex_pattern;
+///
+/// let pattern = imhex_pattern();
+/// assert!(pattern.contains("struct Section9Entry {\n    u32 unk00;"));
+/// assert!(pattern.contains(
+///     "    if (section9_count > 0)\n        Section9Entry section9[section9_count] @ section9_offset;"
+/// ));
+/// assert!(pattern.contains(
+///     "        Section11Entry section11_1[section11_count1] @ section11_offset1;"
+/// ));
+/// assert!(pattern.ends_with("Header header @ 0x00;\n"));
+/// ```
+pub fn imhex_pattern() -> String {
+    let mut out = String::new();
+    write_imhex(&mut out).expect("writing to a String cannot fail");
+    out
+}
+
+/// 010 Editor binary template describing a whole file, starting with the header at offset 0.
+///
+/// Structs are declared the same way as in [`imhex_pattern`]. Offsets are followed by seeking to
+/// them and back, and arrays of structs that contain pointers are declared with
+/// `<optimize=false>` so each element is read on its own.
+///
+/// # Example
+/// ```rust
+/// use fxr_binary_reader::fxr::templates::binary_template;
+///
+/// let template = binary_template();
+/// assert!(template.contains("typedef struct {\n    uint32 data;\n} Section11Entry;"));
+/// assert!(template.contains("        FSeek(section7_offset);\n        Section7Container section7;"));
+/// assert!(template.contains("Section6Entry section6[section6_count] <optimize=false>;"));
+/// assert!(template.ends_with("Header header;\n"));
+/// ```
+pub fn binary_template() -> String {
+    let mut out = String::new();
+    write_binary_template(&mut out).expect("writing to a String cannot fail");
+    out
+}
+
+/// Kaitai Struct (`.ksy`) description of a whole file, for generating parsers in other languages.
+/// Serialize it as YAML to get the `.ksy` file.
+///
+/// Each struct becomes a type named in snake case (`Section6Entry` -> `section6_entry`) with its
+/// fields as `seq` entries, and every `#[fxr(points_to = ...)]` offset becomes an instance placed
+/// at that absolute offset, guarded by its count.
+///
+/// # Example
+/// ```rust
+/// use fxr_binary_reader::fxr::{layout::structs, templates::kaitai_spec};
+///
+/// let spec = kaitai_spec();
+/// assert_eq!(spec["seq"][0]["type"], "header");
+///
+/// // Every struct in the parsed tree matches its Rust layout field by field
+/// let types = spec["types"].as_object().unwrap();
+/// assert_eq!(types.len(), structs().count());
+/// for (name, layout) in structs() {
+///     let id: String = name
+///         .chars()
+///         .flat_map(|c| match c.is_ascii_uppercase() {
+///             true => vec!['_', c.to_ascii_lowercase()],
+///             false => vec![c],
+///         })
+///         .skip(1)
+///         .collect();
+///     let seq = types[id.as_str()]["seq"].as_array().unwrap();
+///     assert_eq!(seq.len(), layout.len(), "{}", name);
+///     for (entry, span) in seq.iter().zip(layout) {
+///         assert_eq!(entry["id"], span.name, "{}", name);
+///         let size = match entry["type"].as_str().unwrap() {
+///             "u1" => 1,
+///             "u2" => 2,
+///             "u4" => 4,
+///             other => panic!("unexpected type {}", other),
+///         };
+///         assert_eq!(size, span.size, "{}.{}", name, span.name);
+///     }
+/// }
+///
+/// // Nested sections are reached through instances at absolute offsets
+/// let section7 = &types["section6_entry"]["instances"]["section7"];
+/// assert_eq!(section7["pos"], "section7_offset");
+/// assert_eq!(section7["type"], "section7_container");
+/// assert_eq!(types["section7_container"]["instances"]["section8"]["repeat-expr"], "section8_count");
+/// ```
+pub fn kaitai_spec() -> Value {
+    let mut types = Map::new();
+    let order = declaration_order();
+    for info in sections_in(&order) {
+        let seq: Vec<Value> = info
+            .fields
+            .iter()
+            .map(|span| match FieldType::of_size(span.size) {
+                Some(ty) => json!({ "id": span.name, "type": kaitai_type(ty) }),
+                None => json!({ "id": span.name, "size": span.size }),
+            })
+            .collect();
+        let mut ty = Map::new();
+        ty.insert("seq".into(), seq.into());
+        if !info.pointers.is_empty() {
+            let mut instances = Map::new();
+            for pointer in info.pointers {
+                let mut instance = Map::new();
+                instance.insert("pos".into(), pointer.field.into());
+                instance.insert("type".into(), snake_case(pointer.target).into());
+                if !pointer.single {
+                    instance.insert("repeat".into(), "expr".into());
+                    instance.insert("repeat-expr".into(), pointer.count.into());
+                }
+                instance.insert("if".into(), format!("{} > 0", pointer.count).into());
+                instances.insert(member_name(pointer), instance.into());
+            }
+            ty.insert("instances".into(), instances.into());
+        }
+        types.insert(snake_case(info.name), ty.into());
+    }
+
+    json!({
+        "meta": {
+            "id": "fxr",
+            "title": "FXR effect file",
+            "file-extension": "fxr",
+            "endian": "le",
+        },
+        "doc": "Generated by `fxr template kaitai` from the reader's section structs.",
+        "seq": [{ "id": "header", "type": "header" }],
+        "types": types,
+    })
+}
+
+fn write_imhex(out: &mut impl Write) -> std::fmt::Result {
+    writeln!(
+        out,
+        "// Generated by `fxr template imhex` from the reader's section structs."
+    )?;
+    writeln!(out, "#pragma description \"FXR effect file\"")?;
+    writeln!(out, "#pragma endian little")?;
+    for info in declaration_order() {
+        writeln!(out)?;
+        writeln!(out, "struct {} {{", info.name)?;
+        for span in info.fields {
+            writeln!(out, "    {};", declaration(span, imhex_type))?;
+        }
+        for pointer in info.pointers {
+            writeln!(out, "    if ({} > 0)", pointer.count)?;
+            if pointer.single {
+                writeln!(
+                    out,
+                    "        {} {} @ {};",
+                    pointer.target,
+                    member_name(pointer),
+                    pointer.field
+                )?;
+            } else {
+                writeln!(
+                    out,
+                    "        {} {}[{}] @ {};",
+                    pointer.target,
+                    member_name(pointer),
+                    pointer.count,
+                    pointer.field
+                )?;
+            }
+        }
+        writeln!(out, "}};")?;
+    }
+    writeln!(out)?;
+    writeln!(out, "Header header @ 0x00;")
+}
+
+fn write_binary_template(out: &mut impl Write) -> std::fmt::Result {
+    writeln!(out, "//------------------------------------------------")?;
+    writeln!(out, "//--- 010 Editor Binary Template")?;
+    writeln!(out, "//   File: fxr.bt")?;
+    writeln!(out, "//   Purpose: FXR effect file")?;
+    writeln!(
+        out,
+        "//   Generated by `fxr template 010` from the reader's section structs."
+    )?;
+    writeln!(out, "//------------------------------------------------")?;
+    writeln!(out, "LittleEndian();")?;
+    for info in declaration_order() {
+        writeln!(out)?;
+        writeln!(out, "typedef struct {{")?;
+        for span in info.fields {
+            writeln!(out, "    {};", declaration(span, bt_type))?;
+        }
+        for pointer in info.pointers {
+            let member = member_name(pointer);
+            writeln!(out, "    if ({} > 0) {{", pointer.count)?;
+            writeln!(out, "        local int64 {}_return = FTell();", member)?;
+            writeln!(out, "        FSeek({});", pointer.field)?;
+            if pointer.single {
+                writeln!(out, "        {} {};", pointer.target, member)?;
+            } else {
+                let optimize = if has_pointers(pointer.target) {
+                    " <optimize=false>"
+                } else {
+                    ""
+                };
+                writeln!(
+                    out,
+                    "        {} {}[{}]{};",
+                    pointer.target, member, pointer.count, optimize
+                )?;
+            }
+            writeln!(out, "        FSeek({}_return);", member)?;
+            writeln!(out, "    }}")?;
+        }
+        writeln!(out, "}} {};", info.name)?;
+    }
+    writeln!(out)?;
+    writeln!(out, "FSeek(0);")?;
+    writeln!(out, "Header header;")
+}
+
+/// The structs reachable from `Header`, each one after every struct it points to, so both
+/// template languages see a type before it is used
+fn declaration_order() -> Vec<&'static SectionInfo> {
+    fn visit(info: &'static SectionInfo, order: &mut Vec<&'static SectionInfo>) {
+        if order.iter().any(|seen| seen.name == info.name) {
+            return;
+        }
+        for pointer in info.pointers {
+            if let Some(target) = section(pointer.target) {
+                visit(target, order);
+            }
+        }
+        order.push(info);
+    }
+
+    let mut order = Vec::new();
+    if let Some(header) = section("Header") {
+        visit(header, &mut order);
+    }
+    order
+}
+
+/// The structs in `order`, sorted back into section order so `Header` comes first
+fn sections_in(order: &[&'static SectionInfo]) -> Vec<&'static SectionInfo> {
+    let mut sorted = order.to_vec();
+    sorted.sort_by_key(|info| sections().iter().position(|other| other.name == info.name));
+    sorted
+}
+
+fn has_pointers(name: &str) -> bool {
+    section(name).is_some_and(|info| !info.pointers.is_empty())
+}
+
+/// `section8_offset` -> `section8`, `section11_offset1` -> `section11_1`
+fn member_name(pointer: &Pointer) -> String {
+    let base = pointer.field.trim_end_matches(|c: char| c.is_ascii_digit());
+    let number = &pointer.field[base.len()..];
+    match base.strip_suffix("_offset") {
+        Some(name) if number.is_empty() => name.to_string(),
+        Some(name) => format!("{}_{}", name, number),
+        None => pointer.field.to_string(),
+    }
+}
+
+/// `u32 name`, or a byte array for a field that isn't 1, 2 or 4 bytes long
+fn declaration(span: &FieldSpan, type_name: fn(FieldType) -> &'static str) -> String {
+    match FieldType::of_size(span.size) {
+        Some(ty) => format!("{} {}", type_name(ty), span.name),
+        None => format!("{} {}[{}]", type_name(FieldType::U8), span.name, span.size),
+    }
+}
+
+/// `Section6Entry` -> `section6_entry`
+fn snake_case(name: &str) -> String {
+    let mut out = String::new();
+    let mut previous_lower = false;
+    for c in name.chars() {
+        if c.is_ascii_uppercase() && previous_lower {
+            out.push('_');
+        }
+        previous_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        out.push(c.to_ascii_lowercase());
+    }
+    out
+}
+
+fn kaitai_type(ty: FieldType) -> &'static str {
+    match ty {
+        FieldType::U8 => "u1",
+        FieldType::U16 => "u2",
+        FieldType::U32 => "u4",
+    }
+}
+
+fn imhex_type(ty: FieldType) -> &'static str {
+    match ty {
+        FieldType::U8 => "u8",
+        FieldType::U16 => "u16",
+        FieldType::U32 => "u32",
+    }
+}
+
+fn bt_type(ty: FieldType) -> &'static str {
+    match ty {
+        FieldType::U8 => "ubyte",
+        FieldType::U16 => "uint16",
+        FieldType::U32 => "uint32",
+    }
+}
@@ -0,0 +1,87 @@
+use super::{
+    Section11Entry,
+    fxr_parser_with_sections::ParsedFXR,
+    walk::{is_offset_field, walk},
+};
+use serde_json::Value;
+use std::fmt::Write;
+
+/// Options for [`canonical_text`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TextconvOptions {
+    /// Leave out `*_offset` fields so that data moving around doesn't show up as changes
+    pub ignore_offsets: bool,
+}
+
+/// Renders a parsed file as deterministic, line-oriented text meant for line-based diff tools
+/// such as `git diff`.
+///
+/// Every field gets its own `path.field = value` line, nodes are ordered by section path and
+/// fields by declaration order, so the output only changes where the file changes. Section11
+/// slots are printed as their typed value followed by the raw bits, e.g.
+/// `Section4::Section6[0]::Section11[0] = 1.0f (0x3F800000)`.
+///
+/// # Example
+/// ```rust
+/// use fxr_binary_reader::fxr::{
+///     fxr_parser_with_sections::parse_fxr,
+///     textconv::{TextconvOptions, canonical_text},
+/// };
+///
+/// let mut data = vec![0u8; 0xA0];
+/// data[0..4].copy_from_slice(b"FXR\0");
+/// data[6..8].copy_from_slice(&5u16.to_le_bytes()); // version
+/// data[8..12].copy_from_slice(&1u32.to_le_bytes()); // unk08
+/// data[0x10..0x14].copy_from_slice(&0x90u32.to_le_bytes()); // section1_offset
+/// data[0x14..0x18].copy_from_slice(&1u32.to_le_bytes()); // section1_count
+/// data[0x68..0x6C].copy_from_slice(&1u32.to_le_bytes()); // unk68
+///
+/// let fxr = parse_fxr(&data).unwrap();
+/// let text = canonical_text(&fxr, TextconvOptions::default());
+/// assert!(text.contains("\nHeader.version = 5\n"));
+/// assert!(text.contains("\nSection1.section2_count = 0\n"));
+/// ```
+pub fn canonical_text(fxr: &ParsedFXR, options: TextconvOptions) -> String {
+    let mut out = String::new();
+    // Writing to a String can't fail
+    let _ = write_canonical_text(&mut out, fxr, options);
+    out
+}
+
+fn write_canonical_text(
+    out: &mut impl Write,
+    fxr: &ParsedFXR,
+    options: TextconvOptions,
+) -> std::fmt::Result {
+    let mut nodes = walk(fxr);
+    nodes.sort_by(|a, b| a.path.cmp(&b.path));
+
+    writeln!(out, "# fxr textconv v1")?;
+    for node in nodes {
+        if let Some(entry) = node.section.get::<Section11Entry>() {
+            writeln!(
+                out,
+                "{} = {} (0x{:08X})",
+                node.path,
+                entry.value(),
+                entry.data
+            )?;
+            continue;
+        }
+        writeln!(out, "{} : {}", node.path, node.section.kind())?;
+        for (field, value) in node.section.fields() {
+            if options.ignore_offsets && is_offset_field(&field) {
+                continue;
+            }
+            writeln!(out, "{}.{} = {}", node.path, field, scalar(&value))?;
+        }
+    }
+    Ok(())
+}
+
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.escape_debug().to_string(),
+        other => other.to_string(),
+    }
+}
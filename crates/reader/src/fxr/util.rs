@@ -31,6 +31,26 @@ pub enum ParseError {
     StandardError(#[from] Box<dyn std::error::Error>),
 }
 
+impl ParseError {
+    /// Absolute offset of the data that couldn't be read, when the error names one
+    ///
+    /// # Example
+    /// ```rust
+    /// use fxr_binary_reader::fxr::util::ParseError;
+    ///
+    /// let error = ParseError::OutOfBounds { offset: 0x1458, size: 0x20, data_len: 0x1460 };
+    /// assert_eq!(error.offset(), Some(0x1458));
+    /// assert_eq!(ParseError::BufferTooSmall { expected: 0x90, actual: 4 }.offset(), None);
+    /// ```
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            ParseError::OutOfBounds { offset, .. } => Some(*offset),
+            ParseError::ParseFailed { start, .. } => Some(*start),
+            _ => None,
+        }
+    }
+}
+
 /// Parses a struct from the given data buffer at the specified offset.
 ///
 /// This function extracts a struct of type `T` from the provided data buffer, starting at the given
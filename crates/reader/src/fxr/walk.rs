@@ -0,0 +1,374 @@
+use super::{
+    Section11Entry,
+    catalog::{SectionInfo, section},
+    fxr_parser_with_sections::ParsedFXR,
+    layout::FieldSpan,
+    parse_section_6_nested::{
+        ParsedSection6,
+        parse_section_7_nested::{ParsedSection7Nested, ParsedSection8},
+    },
+    reflect::{Field, Reflect, reflect},
+};
+use serde::{Serialize, Serializer};
+use std::fmt::{Display, Formatter, UpperHex};
+use zerocopy::{FromBytes, KnownLayout, Ref};
+
+/// One step of a [`SectionPath`]: a section number and, for arrays, the index of the entry.
+/// Section number `0` is the header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PathSegment {
+    pub section: u8,
+    pub index: Option<usize>,
+}
+
+impl Display for PathSegment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.section {
+            0 => write!(f, "Header")?,
+            n => write!(f, "Section{}", n)?,
+        }
+        if let Some(index) = self.index {
+            write!(f, "[{}]", index)?;
+        }
+        Ok(())
+    }
+}
+
+/// Location of a struct within the parsed tree, independent of its byte offset.
+///
+/// Paths follow the logical nesting of the format, so the same node in two versions of a file has
+/// the same path even if the data moved around, e.g. `Section4::Section6[2]::Section7::Section8[0]`.
+/// Ordering is by section number and index, with parents before their children.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SectionPath(Vec<PathSegment>);
+
+impl SectionPath {
+    /// Returns the path of a child of this node
+    pub fn child(&self, section: u8, index: Option<usize>) -> SectionPath {
+        let mut segments = self.0.clone();
+        segments.push(PathSegment { section, index });
+        SectionPath(segments)
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.0
+    }
+
+    /// Returns the path of the enclosing node, or `None` for top-level nodes such as `Section1`
+    pub fn parent(&self) -> Option<SectionPath> {
+        match self.0.as_slice() {
+            [] | [_] => None,
+            [parent @ .., _] => Some(SectionPath(parent.to_vec())),
+        }
+    }
+
+    /// Returns true if `self` is `other` or one of its descendants
+    pub fn starts_with(&self, other: &SectionPath) -> bool {
+        self.0.starts_with(&other.0)
+    }
+}
+
+impl Display for SectionPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, segment) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, "::")?;
+            }
+            write!(f, "{}", segment)?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for SectionPath {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// A borrowed section struct of any type registered with `#[derive(FxrSection)]`
+///
+/// # Example
+/// ```rust
+/// use fxr_binary_reader::fxr::{Section9Entry, Section11Entry, walk::SectionRef};
+/// use zerocopy::FromBytes;
+///
+/// let bytes = [0u8; 0x18];
+/// let entry = Section9Entry::ref_from_bytes(&bytes).unwrap();
+/// let section = SectionRef::new(entry);
+/// assert_eq!(section.kind(), "Section9Entry");
+/// assert_eq!(section.layout().len(), 6);
+/// assert!(section.get::<Section9Entry>().is_some());
+/// assert!(section.get::<Section11Entry>().is_none());
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct SectionRef<'a> {
+    info: &'static SectionInfo,
+    bytes: &'a [u8],
+}
+
+impl<'a> SectionRef<'a> {
+    pub fn new<T: Reflect>(value: &'a T) -> Self {
+        Self {
+            info: section(T::NAME).expect("FxrSection registers every struct in the catalog"),
+            bytes: value.as_bytes(),
+        }
+    }
+
+    /// Name of the struct, e.g. `Section6Entry`
+    pub fn kind(&self) -> &'static str {
+        self.info.name
+    }
+
+    /// Catalog entry of the struct type
+    pub fn info(&self) -> &'static SectionInfo {
+        self.info
+    }
+
+    /// Raw bytes of the struct as stored in the file
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Byte ranges of the struct's fields, in declaration order
+    pub fn layout(&self) -> &'static [FieldSpan] {
+        self.info.fields
+    }
+
+    /// The struct as a `T`, or `None` if it is another type
+    pub fn get<T: Reflect + FromBytes + KnownLayout>(&self) -> Option<&'a T> {
+        if self.info.name != T::NAME {
+            return None;
+        }
+        T::ref_from_bytes(self.bytes).ok()
+    }
+
+    /// Every field with its offset, type and raw bytes, in declaration order
+    pub fn reflect(&self) -> Vec<Field<'a>> {
+        reflect(self.as_bytes(), self.layout())
+    }
+
+    /// Field names and values, in declaration order, read from the raw bytes so any bit pattern
+    /// is accepted. Offsets are written in hex, e.g. `"0x90"`, and everything else as a number.
+    ///
+    /// # Example
+    /// ```rust
+    /// use fxr_binary_reader::fxr::{Header, walk::SectionRef};
+    /// use serde_json::json;
+    /// use zerocopy::FromBytes;
+    ///
+    /// let mut bytes = [0u8; size_of::<Header>()];
+    /// bytes[0..4].copy_from_slice(&[0xFF; 4]); // magic, not UTF-8
+    /// bytes[0x10..0x14].copy_from_slice(&0x90u32.to_le_bytes()); // section1_offset
+    /// let header = Header::ref_from_bytes(&bytes).unwrap();
+    ///
+    /// let fields = SectionRef::new(header).fields();
+    /// assert_eq!(fields[0], ("magic".to_string(), json!(0xFFFF_FFFFu32)));
+    /// assert_eq!(fields[5], ("section1_offset".to_string(), json!("0x90")));
+    /// ```
+    pub fn fields(&self) -> Vec<(String, serde_json::Value)> {
+        self.reflect()
+            .into_iter()
+            .map(|field| {
+                let value = field_json(field.name, field.value().as_u64());
+                (field.name.to_string(), value)
+            })
+            .collect()
+    }
+}
+
+/// Returns true for pointer fields such as `section7_offset` or `section11_offset1`, whose values
+/// change whenever data earlier in the file grows or shrinks
+pub fn is_offset_field(field: &str) -> bool {
+    field
+        .trim_end_matches(char::is_numeric)
+        .ends_with("_offset")
+}
+
+/// An offset the way every exporter writes it, e.g. `0x1A0`
+pub fn format_offset(offset: impl UpperHex) -> String {
+    format!("0x{:X}", offset)
+}
+
+/// A field value as exported to JSON: a [`format_offset`] string for offset fields, a number
+/// otherwise, matching the formats in [`super::formats::registry`]
+pub fn field_json(field: &str, value: u64) -> serde_json::Value {
+    match is_offset_field(field) {
+        true => format_offset(value).into(),
+        false => value.into(),
+    }
+}
+
+/// A struct found in the parsed tree along with where it lives
+#[derive(Debug, Clone)]
+pub struct Node<'a> {
+    pub path: SectionPath,
+    /// Absolute offset of the struct in the file
+    pub offset: usize,
+    pub section: SectionRef<'a>,
+}
+
+/// Flattens a parsed file into the list of every struct it contains, parents before children.
+///
+/// Section11 arrays are expanded into one node per slot so each value can be addressed by path,
+/// e.g. `Section4::Section6[0]::Section11[4]`.
+///
+/// # Example
+/// ```rust
+/// use fxr_binary_reader::fxr::{fxr_parser_with_sections::parse_fxr, walk::walk};
+///
+/// let mut data = vec![0u8; 0xA0];
+/// data[0..4].copy_from_slice(b"FXR\0");
+/// data[6..8].copy_from_slice(&5u16.to_le_bytes()); // version
+/// data[8..12].copy_from_slice(&1u32.to_le_bytes()); // unk08
+/// data[0x10..0x14].copy_from_slice(&0x90u32.to_le_bytes()); // section1_offset
+/// data[0x14..0x18].copy_from_slice(&1u32.to_le_bytes()); // section1_count
+/// data[0x68..0x6C].copy_from_slice(&1u32.to_le_bytes()); // unk68
+///
+/// let fxr = parse_fxr(&data).unwrap();
+/// let nodes = walk(&fxr);
+/// assert_eq!(nodes.len(), 2);
+/// assert_eq!(nodes[1].path.to_string(), "Section1");
+/// assert_eq!(nodes[1].offset, 0x90);
+/// assert_eq!(nodes[1].section.kind(), "Section1Container");
+/// ```
+pub fn walk<'r>(fxr: &'r ParsedFXR<'_>) -> Vec<Node<'r>> {
+    let mut walker = Walker {
+        base: Ref::bytes(&fxr.header).as_ptr() as usize,
+        nodes: Vec::new(),
+    };
+    let root = SectionPath::default();
+    walker.push(root.child(0, None), SectionRef::new(&*fxr.header));
+
+    if let Some(tree) = &fxr.section1_tree {
+        let section1 = root.child(1, None);
+        walker.push(section1.clone(), SectionRef::new(&*tree.section1));
+        if let Some(section2) = &tree.section2 {
+            let section2_path = section1.child(2, None);
+            walker.push(section2_path.clone(), SectionRef::new(&**section2));
+            for (i, entry) in tree.section3.iter().flat_map(|s| s.iter()).enumerate() {
+                walker.push(section2_path.child(3, Some(i)), SectionRef::new(entry));
+            }
+        }
+    }
+
+    if let Some(tree) = &fxr.section4_tree {
+        let section4 = root.child(4, None);
+        walker.push(section4.clone(), SectionRef::new(&*tree.container));
+        for (i, entry) in tree
+            .section4_entries
+            .iter()
+            .flat_map(|s| s.iter())
+            .enumerate()
+        {
+            walker.push(section4.child(4, Some(i)), SectionRef::new(entry));
+        }
+        for (i, entry) in tree
+            .section5_entries
+            .iter()
+            .flat_map(|s| s.iter())
+            .enumerate()
+        {
+            walker.push(section4.child(5, Some(i)), SectionRef::new(entry));
+        }
+        let section6_entries = tree.section6_entries.iter().flat_map(|s| s.iter());
+        for (i, (entry, nested)) in section6_entries.zip(&tree.section6_nested).enumerate() {
+            let section6 = section4.child(6, Some(i));
+            walker.push(section6.clone(), SectionRef::new(entry));
+            walker.section6(&section6, nested);
+        }
+    }
+
+    for (i, entry) in fxr
+        .section12_entries
+        .iter()
+        .flat_map(|s| s.iter())
+        .enumerate()
+    {
+        walker.push(root.child(12, Some(i)), SectionRef::new(entry));
+    }
+    for (i, entry) in fxr
+        .section13_entries
+        .iter()
+        .flat_map(|s| s.iter())
+        .enumerate()
+    {
+        walker.push(root.child(13, Some(i)), SectionRef::new(entry));
+    }
+    for (i, entry) in fxr
+        .section14_entries
+        .iter()
+        .flat_map(|s| s.iter())
+        .enumerate()
+    {
+        walker.push(root.child(14, Some(i)), SectionRef::new(entry));
+    }
+
+    walker.nodes
+}
+
+struct Walker<'r> {
+    base: usize,
+    nodes: Vec<Node<'r>>,
+}
+
+impl<'r> Walker<'r> {
+    fn push(&mut self, path: SectionPath, section: SectionRef<'r>) {
+        let offset = section.as_bytes().as_ptr() as usize - self.base;
+        self.nodes.push(Node {
+            path,
+            offset,
+            section,
+        });
+    }
+
+    fn section11<'s>(
+        &mut self,
+        parent: &SectionPath,
+        slices: impl IntoIterator<Item = &'r Ref<&'s [u8], [Section11Entry]>>,
+    ) where
+        's: 'r,
+    {
+        let entries = slices.into_iter().flat_map(|s| s.iter());
+        for (i, entry) in entries.enumerate() {
+            self.push(parent.child(11, Some(i)), SectionRef::new(entry));
+        }
+    }
+
+    fn section6(&mut self, path: &SectionPath, nested: &'r ParsedSection6<'_>) {
+        self.section11(path, &nested.section11);
+        if let Some(section10) = &nested.section10 {
+            let section10_path = path.child(10, None);
+            self.push(
+                section10_path.clone(),
+                SectionRef::new(&*section10.container),
+            );
+            self.section11(&section10_path, &section10.section11);
+        }
+        if let Some(section7) = &nested.section7 {
+            let section7_path = path.child(7, None);
+            self.push(section7_path.clone(), SectionRef::new(&*section7.container));
+            self.section7(&section7_path, &section7.nested);
+        }
+    }
+
+    fn section7(&mut self, path: &SectionPath, nested: &'r ParsedSection7Nested<'_>) {
+        self.section11(path, &nested.section11);
+        let entries = nested.section8_entries.iter().flat_map(|s| s.iter());
+        for (i, (entry, section8)) in entries.zip(&nested.section8).enumerate() {
+            let section8_path = path.child(8, Some(i));
+            self.push(section8_path.clone(), SectionRef::new(entry));
+            self.section8(&section8_path, section8);
+        }
+    }
+
+    fn section8(&mut self, path: &SectionPath, section8: &'r ParsedSection8<'_>) {
+        self.section11(path, &section8.section11);
+        let entries = section8.section9_entries.iter().flat_map(|s| s.iter());
+        for (j, (entry, section9)) in entries.zip(&section8.section9).enumerate() {
+            let section9_path = path.child(9, Some(j));
+            self.push(section9_path.clone(), SectionRef::new(entry));
+            self.section11(&section9_path, &section9.section11);
+        }
+    }
+}
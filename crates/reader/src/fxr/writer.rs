@@ -0,0 +1,72 @@
+use zerocopy::{Immutable, IntoBytes};
+
+/// Builds an FXR file image by placing section structs at absolute offsets.
+///
+/// The writer doesn't lay anything out by itself: callers decide where each struct goes and keep
+/// the header and container pointers consistent. Writing past the end grows the image,
+/// zero-filling any gap.
+///
+/// # Example
+/// ```rust
+/// use fxr_binary_reader::fxr::{Header, Section11Entry, writer::FxrWriter};
+///
+/// let mut writer = FxrWriter::new();
+/// writer.write_struct(0, &Header::default());
+/// writer.write_struct(0x94, &Section11Entry { data: 1.0f32.to_bits() });
+///
+/// let bytes = writer.into_bytes();
+/// assert_eq!(bytes.len(), 0x98);
+/// assert_eq!(&bytes[0..4], b"FXR\0");
+/// assert_eq!(&bytes[0x94..0x98], &[0x00, 0x00, 0x80, 0x3F]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FxrWriter {
+    bytes: Vec<u8>,
+}
+
+impl FxrWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts from an existing file image, e.g. to patch fields in place
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Self { bytes }
+    }
+
+    /// Writes `value` with its first byte at `offset`
+    pub fn write_struct<T: IntoBytes + Immutable>(&mut self, offset: usize, value: &T) {
+        self.write_bytes(offset, value.as_bytes());
+    }
+
+    /// Copies `bytes` into the image starting at `offset`
+    pub fn write_bytes(&mut self, offset: usize, bytes: &[u8]) {
+        let end = offset + bytes.len();
+        self.pad_to(end);
+        self.bytes[offset..end].copy_from_slice(bytes);
+    }
+
+    /// Zero-extends the image to at least `len` bytes
+    pub fn pad_to(&mut self, len: usize) {
+        if self.bytes.len() < len {
+            self.bytes.resize(len, 0);
+        }
+    }
+
+    /// Current length of the image in bytes
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
@@ -1,2 +1,4 @@
 #![deny(clippy::unwrap_used)]
+// Lets `#[derive(FxrSection)]` refer to this crate by name from inside it
+extern crate self as fxr_binary_reader;
 pub mod fxr;